@@ -0,0 +1,126 @@
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{fmt, io};
+
+// Sent to the frontend as `{ kind, message }` so the UI can branch on `kind` instead of
+// matching message text.
+#[derive(Debug)]
+pub enum LauncherError {
+    SteamNotFound,
+    SteamStartTimeout(u64),
+    WorkshopPathEmpty,
+    InvalidWorkshopPath(String),
+    InvalidWorkshopId(String),
+    PzInstallNotFound,
+    PzNotReady,
+    PzAlreadyRunning(String),
+    OptimizationsSourceMissing(String),
+    InstallNotWritable(String),
+    InvalidLaunchArg(String),
+    Io(String),
+    Network(String),
+    Other(String),
+}
+
+impl LauncherError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LauncherError::SteamNotFound => "SteamNotFound",
+            LauncherError::SteamStartTimeout(_) => "SteamStartTimeout",
+            LauncherError::WorkshopPathEmpty => "WorkshopPathEmpty",
+            LauncherError::InvalidWorkshopPath(_) => "InvalidWorkshopPath",
+            LauncherError::InvalidWorkshopId(_) => "InvalidWorkshopId",
+            LauncherError::PzInstallNotFound => "PzInstallNotFound",
+            LauncherError::PzNotReady => "PzNotReady",
+            LauncherError::PzAlreadyRunning(_) => "PzAlreadyRunning",
+            LauncherError::OptimizationsSourceMissing(_) => "OptimizationsSourceMissing",
+            LauncherError::InstallNotWritable(_) => "InstallNotWritable",
+            LauncherError::InvalidLaunchArg(_) => "InvalidLaunchArg",
+            LauncherError::Io(_) => "Io",
+            LauncherError::Network(_) => "Network",
+            LauncherError::Other(_) => "Other",
+        }
+    }
+}
+
+impl fmt::Display for LauncherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LauncherError::SteamNotFound => write!(f, "Steam installation not found"),
+            LauncherError::SteamStartTimeout(secs) => {
+                write!(f, "Steam did not start within {} seconds", secs)
+            }
+            LauncherError::WorkshopPathEmpty => write!(f, "Workshop path is empty"),
+            LauncherError::InvalidWorkshopPath(reason) => {
+                write!(f, "Invalid workshop path: {}", reason)
+            }
+            LauncherError::InvalidWorkshopId(id) => write!(f, "Invalid workshop ID \"{}\"", id),
+            LauncherError::PzInstallNotFound => {
+                write!(f, "Could not locate ProjectZomboid install directory")
+            }
+            LauncherError::PzNotReady => write!(
+                f,
+                "Project Zomboid is still downloading or updating in Steam; wait for it to finish before launching"
+            ),
+            LauncherError::PzAlreadyRunning(exe) => {
+                write!(f, "Project Zomboid is already running ({})", exe)
+            }
+            LauncherError::OptimizationsSourceMissing(path) => {
+                write!(f, "Optimizations folder not found: {}", path)
+            }
+            LauncherError::InstallNotWritable(detail) => write!(
+                f,
+                "{}. Run the launcher as administrator or move the Steam library out of Program Files.",
+                detail
+            ),
+            LauncherError::InvalidLaunchArg(arg) => write!(
+                f,
+                "Launch argument \"{}\" is not allowed; it contains special characters or overrides a launcher-managed option",
+                arg
+            ),
+            LauncherError::Io(msg) | LauncherError::Network(msg) | LauncherError::Other(msg) => {
+                write!(f, "{}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LauncherError {}
+
+impl Serialize for LauncherError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LauncherError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<io::Error> for LauncherError {
+    fn from(err: io::Error) -> Self {
+        LauncherError::Io(err.to_string())
+    }
+}
+
+impl From<reqwest::Error> for LauncherError {
+    fn from(err: reqwest::Error) -> Self {
+        LauncherError::Network(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for LauncherError {
+    fn from(err: serde_json::Error) -> Self {
+        LauncherError::Other(err.to_string())
+    }
+}
+
+impl From<String> for LauncherError {
+    fn from(msg: String) -> Self {
+        LauncherError::Other(msg)
+    }
+}
+
+impl From<&str> for LauncherError {
+    fn from(msg: &str) -> Self {
+        LauncherError::Other(msg.to_string())
+    }
+}
@@ -0,0 +1,100 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::Manager;
+
+use crate::error::LauncherError;
+
+const LOG_FILE: &str = "launcher.log";
+// Game stdout/stderr from a debug launch; overwritten each time
+const PZ_CONSOLE_LOG: &str = "launcher-pz-console.log";
+// launcher.log plus launcher.1.log .. launcher.4.log from earlier runs
+const KEEP_LOGS: usize = 5;
+
+struct FileLogger {
+    file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{} [{}] {}", ts, record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+pub fn log_file_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, LauncherError> {
+    app_handle
+        .path()
+        .app_log_dir()
+        .map(|dir| dir.join(LOG_FILE))
+        .map_err(|e| LauncherError::Other(e.to_string()))
+}
+
+pub fn pz_console_log_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, LauncherError> {
+    let path = log_file_path(app_handle)?.with_file_name(PZ_CONSOLE_LOG);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(path)
+}
+
+fn rotated_path(path: &std::path::Path, index: usize) -> PathBuf {
+    path.with_file_name(format!("launcher.{}.log", index))
+}
+
+// Shifts launcher.log -> launcher.1.log -> ... and drops the oldest.
+fn rotate(path: &std::path::Path) {
+    let _ = fs::remove_file(rotated_path(path, KEEP_LOGS - 1));
+    for index in (1..KEEP_LOGS - 1).rev() {
+        let _ = fs::rename(rotated_path(path, index), rotated_path(path, index + 1));
+    }
+    let _ = fs::rename(path, rotated_path(path, 1));
+}
+
+pub fn init(app_handle: &tauri::AppHandle) -> Result<(), LauncherError> {
+    let path = log_file_path(app_handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    rotate(&path);
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    log::set_boxed_logger(Box::new(FileLogger {
+        file: Mutex::new(file),
+    }))
+    .map_err(|e| io::Error::other(e.to_string()))?;
+    log::set_max_level(LevelFilter::Info);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn open_log_file(app_handle: tauri::AppHandle) -> Result<String, LauncherError> {
+    let path = log_file_path(&app_handle)?;
+    if !path.exists() {
+        return Err("The launcher has not written a log file yet".into());
+    }
+    open::that(&path)?;
+    Ok(path.to_string_lossy().to_string())
+}
@@ -2,29 +2,132 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+#[cfg(windows)]
+use winreg::{
+    enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
+    RegKey,
+};
+
+mod error;
+mod logging;
+mod process_monitor;
+mod settings;
+mod telemetry;
 
-use sysinfo::System;
-use tauri::Emitter;
+use error::LauncherError;
+use process_monitor::{ProcessInfo, ProcessMonitor, ProcessSnapshot};
+use tauri::{Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
 
 use std::{
+    collections::{HashMap, HashSet},
     fs,
     io::{self, Read, Write},
-    net::ToSocketAddrs,
+    net::{TcpStream, ToSocketAddrs, UdpSocket},
     path::{Path, PathBuf},
     process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     thread,
-    time::Duration,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 const APPID: &str = "108600"; // PZ
 const SERVER_IP: &str = "13thpandemic.mywire.org";
 const SERVER_PORT: u16 = 16261;
+// Steam query port; PZ answers A2S on the game port unless the server config moves it.
+const SERVER_QUERY_PORT: u16 = SERVER_PORT;
+const STEAM_COLLECTION_API: &str =
+    "https://api.steampowered.com/ISteamRemoteStorage/GetCollectionDetails/v1/";
+// Shared by every socket and API call so a dead network can't hang a command.
+const NET_TIMEOUT: Duration = Duration::from_secs(3);
+const STEAM_START_TIMEOUT: Duration = Duration::from_secs(30);
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const REQUIRED_FILES_URL: &str = "https://13thpandemic.mywire.org/launcher/required-files.json";
+const MODPACK_VERSION_URL: &str = "https://13thpandemic.mywire.org/launcher/version.json";
+const LAUNCHER_UPDATE_URL: &str = "https://13thpandemic.mywire.org/launcher/latest.json";
+const WORKSHOP_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Large packs on slow connections can take a long time; after this the UI falls back to Refresh.
+const SUBSCRIBE_WAIT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const LAUNCH_DEBOUNCE: Duration = Duration::from_secs(10);
+const APPLY_HISTORY_LIMIT: usize = 50;
+const LAUNCH_TIMES_LIMIT: usize = 20;
+// Copies slower than this on a multi-second apply are far below any local disk's speed.
+const SLOW_COPY_BYTES_PER_SEC: u64 = 5 * 1024 * 1024;
+const SLOW_COPY_MIN_DURATION: Duration = Duration::from_secs(5);
+// Minimum video memory the modpack's graphics optimizations are tuned for.
+const MIN_GPU_VRAM_MB: u64 = 2048;
+#[cfg(windows)]
+const DISPLAY_ADAPTER_CLASS: &str =
+    "SYSTEM\\CurrentControlSet\\Control\\Class\\{4d36e968-e325-11ce-bfc1-08002be10318}";
 
 #[derive(Serialize)]
 struct DetectResp {
     steam_root: String,
     workshop_path: String,
+    steam_found: bool,
+    pz_installed: bool,
+    pz_ready: bool,
+    install_issue: Option<String>,
+    case_warning: Option<String>,
+    workshop_items: Vec<WorkshopItemStatus>,
+}
+
+#[derive(Serialize)]
+struct WorkshopItemStatus {
+    id: String,
+    path: Option<String>,
+    found: bool,
+}
+
+struct AppManifestInfo {
+    installdir: String,
+    // SteamID64 of the account whose license installed the game
+    last_owner: Option<String>,
+    state_flags: Option<u32>,
+}
+
+// Timestamp of the last -applaunch that has not yet produced a game process.
+#[derive(Default)]
+struct LaunchGuard {
+    pending_since: Mutex<Option<Instant>>,
+    // Furthest phase the current launch got to, for telemetry
+    last_phase: Mutex<Option<LaunchPhase>>,
+}
+
+// Each session watcher gets its own token, so a new launch can't clear a cancel meant for the
+// running one.
+#[derive(Default)]
+struct WatchToken {
+    // Frontend teardown or app exit; the watcher still reports the session as ended
+    cancelled: AtomicBool,
+    // A newer launch took over; its watcher reports the session instead
+    superseded: AtomicBool,
+}
+
+#[derive(Default)]
+struct SessionWatch {
+    current: Mutex<Arc<WatchToken>>,
+}
+
+impl SessionWatch {
+    fn cancel(&self) {
+        self.current
+            .lock()
+            .unwrap()
+            .cancelled
+            .store(true, Ordering::SeqCst);
+    }
+
+    // Retires any running watcher and hands out the token for the next one.
+    fn replace(&self) -> Arc<WatchToken> {
+        let token = Arc::new(WatchToken::default());
+        let previous = std::mem::replace(&mut *self.current.lock().unwrap(), token.clone());
+        previous.superseded.store(true, Ordering::SeqCst);
+        token
+    }
 }
 
 #[derive(Serialize)]
@@ -33,6 +136,188 @@ struct ServerStatus {
     ping_ms: Option<u64>,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+struct ServerProfile {
+    name: String,
+    ip: String,
+    port: u16,
+}
+
+impl Default for ServerProfile {
+    fn default() -> Self {
+        ServerProfile {
+            name: "13th Pandemic".into(),
+            ip: SERVER_IP.into(),
+            port: SERVER_PORT,
+        }
+    }
+}
+
+// `remote` is None when the version endpoint could not be reached or parsed.
+#[derive(Serialize)]
+struct UpdateStatus {
+    local: String,
+    remote: Option<String>,
+    update_available: bool,
+}
+
+// tcp_ok/udp_ok stay false when the name doesn't resolve, so dns_ok separates the two failures.
+#[derive(Serialize)]
+struct PreflightReport {
+    dns_ok: bool,
+    tcp_ok: bool,
+    udp_ok: bool,
+}
+
+// `latest`/`download_url` are None when the update endpoint could not be reached or parsed.
+#[derive(Serialize)]
+struct LauncherUpdate {
+    current: String,
+    latest: Option<String>,
+    update_available: bool,
+    download_url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyReport {
+    ok: bool,
+    missing: Vec<String>,
+    mismatched: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ServerInfo {
+    name: String,
+    map: String,
+    players: u8,
+    max_players: u8,
+}
+
+#[derive(Serialize)]
+struct ServerReachability {
+    reachable: bool,
+    latency_ms: Option<u64>,
+    timed_out: bool,
+}
+
+#[derive(Serialize)]
+struct CollectionCheck {
+    workshop_id: String,
+    content_found: bool,
+    is_collection: bool,
+    children: Vec<String>,
+    missing_children: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct OutboundPortCheck {
+    port: u16,
+    allowed: bool,
+    reason: String,
+    blocking_rule: Option<String>,
+    suggested_rule: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LaunchArgsCheck {
+    localconfig: Option<String>,
+    launch_options: Option<String>,
+    expected_cachedir: String,
+    cachedir_present: bool,
+    steam_running: bool,
+    warning: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CachedirJunction {
+    is_junction: bool,
+    path: String,
+    target: Option<String>,
+    target_available: bool,
+}
+
+#[derive(Serialize)]
+struct StalePack {
+    id: String,
+    path: String,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+struct InstallCompleteness {
+    install_dir: String,
+    complete: bool,
+    missing: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct GpuInfo {
+    name: String,
+    vendor: String,
+    vram_mb: u64,
+}
+
+#[derive(Serialize)]
+struct GpuCompatibility {
+    meets_minimum: bool,
+    gpu: String,
+    notes: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct FirstRunResult {
+    completed: bool,
+    blocked_at: Option<String>,
+    action: Option<String>,
+    workshop_path: String,
+}
+
+#[derive(Serialize)]
+struct AccountOwnership {
+    account: String,
+    persona: String,
+    steam_id: String,
+    most_recent: bool,
+    owns_pz: bool,
+}
+
+struct SteamLoginUser {
+    steam_id: String,
+    account_name: String,
+    persona_name: String,
+    most_recent: bool,
+    remember_password: bool,
+}
+
+#[derive(Serialize)]
+struct OfflineReadiness {
+    steam_offline_ready: bool,
+    pz_installed: bool,
+    files_applied: bool,
+    saves_present: bool,
+}
+
+#[derive(Serialize)]
+struct BackupEntry {
+    timestamp: u64,
+    path: String,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+struct BackupUsage {
+    backups: Vec<BackupEntry>,
+    total_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct OptStatus {
+    source_found: bool,
+    applied: bool,
+    files_total: usize,
+    files_differing: usize,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct ManifestEntry {
     path: String,
@@ -40,11 +325,82 @@ struct ManifestEntry {
     hash: String,
 }
 
+#[derive(Serialize, Default)]
+struct ApplyStats {
+    skipped_fast: u64,
+    hashed: u64,
+    copied: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ApplyRecord {
+    timestamp: u64,
+    success: bool,
+    already: bool,
+    copied: u64,
+    replaced: u64,
+    backed_up: u64,
+    duration_ms: u64,
+    error: Option<String>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct OptimizationManifest {
     entries: Vec<ManifestEntry>,
 }
 
+// Proton installs live in one of these depending on how Steam itself was installed.
+#[cfg(target_os = "linux")]
+fn steam_root_from_fs() -> Option<String> {
+    let home = PathBuf::from(std::env::var_os("HOME")?);
+    [
+        home.join(".steam").join("steam"),
+        home.join(".local").join("share").join("Steam"),
+        home.join(".var")
+            .join("app")
+            .join("com.valvesoftware.Steam")
+            .join(".local")
+            .join("share")
+            .join("Steam"),
+    ]
+    .into_iter()
+    .find(|root| root.join("steamapps").is_dir())
+    .map(|root| root.to_string_lossy().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn steam_root_from_fs() -> Option<String> {
+    let root = PathBuf::from(std::env::var_os("HOME")?)
+        .join("Library")
+        .join("Application Support")
+        .join("Steam");
+    if root.join("steamapps").is_dir() {
+        Some(root.to_string_lossy().to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn steam_root_from_fs() -> Option<String> {
+    None
+}
+
+fn detect_steam_root() -> Option<String> {
+    steam_root_from_registry()
+        .or_else(steam_root_from_fs)
+        .filter(|root| Path::new(root).join("steamapps").is_dir())
+}
+
+fn steam_root() -> String {
+    detect_steam_root().unwrap_or_else(|| "C:/Program Files (x86)/Steam".to_string())
+}
+
+fn require_steam_root() -> Result<String, LauncherError> {
+    detect_steam_root().ok_or(LauncherError::SteamNotFound)
+}
+
+#[cfg(windows)]
 fn steam_root_from_registry() -> Option<String> {
     if let Ok(hkcu) = RegKey::predef(HKEY_CURRENT_USER).open_subkey("Software\\Valve\\Steam") {
         if let Ok(sp) = hkcu.get_value::<String, _>("SteamPath") {
@@ -54,13 +410,129 @@ fn steam_root_from_registry() -> Option<String> {
     None
 }
 
+#[cfg(not(windows))]
+fn steam_root_from_registry() -> Option<String> {
+    None
+}
+
+// Backslashes are only meaningful to Windows; elsewhere they'd break the path.
+fn native_path_string(p: &Path) -> String {
+    let path = p.to_string_lossy();
+    if cfg!(windows) {
+        path.replace('/', "\\")
+    } else {
+        path.to_string()
+    }
+}
+
+const FLATPAK_STEAM_APP_ID: &str = "com.valvesoftware.Steam";
+
+// ~/.var/app/com.valvesoftware.Steam when `steam_root` belongs to Flatpak Steam.
+fn flatpak_app_dir(steam_root: &Path) -> Option<&Path> {
+    steam_root.ancestors().find(|dir| {
+        dir.file_name().is_some_and(|n| n == FLATPAK_STEAM_APP_ID)
+            && dir
+                .parent()
+                .and_then(|p| p.file_name())
+                .is_some_and(|n| n == "app")
+    })
+}
+
+// Inside the sandbox the home directory is the app's data dir, so libraryfolders.vdf records
+// ~/.local/share/Steam for what is really ~/.var/app/<id>/.local/share/Steam on the host.
+fn flatpak_host_path(app_dir: &Path, sandbox_path: &Path) -> PathBuf {
+    let home = app_dir.ancestors().nth(3);
+    match home.and_then(|home| sandbox_path.strip_prefix(home).ok()) {
+        Some(rest) => app_dir.join(rest),
+        None => sandbox_path.to_path_buf(),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum VdfToken {
+    Str(String),
+    Open,
+    Close,
+}
+
+// Minimal KeyValues tokenizer: quoted strings with \\ \" \n \t escapes, bare words, braces and
+// `//` comments.
+fn vdf_tokens(text: &str) -> Vec<VdfToken> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(VdfToken::Open),
+            '}' => tokens.push(VdfToken::Close),
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(other) => value.push(other),
+                            None => {}
+                        },
+                        _ => value.push(c),
+                    }
+                }
+                tokens.push(VdfToken::Str(value));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut value = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == '{' || next == '}' || next == '"' {
+                        break;
+                    }
+                    value.push(next);
+                    chars.next();
+                }
+                tokens.push(VdfToken::Str(value));
+            }
+        }
+    }
+    tokens
+}
+
+// Every `"path" "<value>"` pair, unescaped, in file order.
+fn library_paths_from_vdf(text: &str) -> Vec<PathBuf> {
+    let tokens = vdf_tokens(text);
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match (&tokens[i], tokens.get(i + 1)) {
+            (VdfToken::Str(key), Some(VdfToken::Str(value))) => {
+                if key.eq_ignore_ascii_case("path") {
+                    paths.push(PathBuf::from(value));
+                }
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+    paths
+}
+
 fn parse_libraryfolders(steam_root: &str) -> Vec<PathBuf> {
     let mut libs = vec![PathBuf::from(steam_root).join("steamapps")];
     let vdf = libs[0].join("libraryfolders.vdf");
+    let flatpak = flatpak_app_dir(Path::new(steam_root));
     if let Ok(txt) = fs::read_to_string(&vdf) {
-        let re = Regex::new(r#"path"\s*"([^"]+)"#).unwrap();
-        for cap in re.captures_iter(&txt) {
-            let p = PathBuf::from(&cap[1]).join("steamapps");
+        for path in library_paths_from_vdf(&txt) {
+            let mut p = path.join("steamapps");
+            if let (Some(app_dir), false) = (flatpak, p.exists()) {
+                p = flatpak_host_path(app_dir, &p);
+            }
             if p.exists() {
                 libs.push(p)
             }
@@ -69,550 +541,4743 @@ fn parse_libraryfolders(steam_root: &str) -> Vec<PathBuf> {
     libs
 }
 
-fn find_workshop_item(steam_root: &str, workshop_id: &str) -> Option<String> {
-    for lib in parse_libraryfolders(steam_root) {
+// Steam workshop IDs are 64-bit numbers; anything else would build a bogus URL or path.
+fn validate_workshop_id(workshop_id: &str) -> Result<(), LauncherError> {
+    if workshop_id.parse::<u64>().is_ok() && workshop_id.bytes().all(|b| b.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(LauncherError::InvalidWorkshopId(workshop_id.to_string()))
+    }
+}
+
+// Accepts a bare ID or a pasted workshop link, whose ID sits in the `id` query parameter.
+fn normalize_workshop_id(raw: &str) -> Result<String, LauncherError> {
+    let trimmed = raw.trim();
+    let id = trimmed
+        .split(['?', '&'])
+        .find_map(|part| part.strip_prefix("id="))
+        .unwrap_or(trimmed);
+    validate_workshop_id(id).map_err(|_| LauncherError::InvalidWorkshopId(trimmed.to_string()))?;
+    Ok(id.to_string())
+}
+
+fn normalize_workshop_ids(raw: &[String]) -> Result<Vec<String>, LauncherError> {
+    raw.iter().map(|id| normalize_workshop_id(id)).collect()
+}
+
+fn find_workshop_item(libs: &[PathBuf], workshop_id: &str) -> Option<String> {
+    validate_workshop_id(workshop_id).ok()?;
+    for lib in libs {
         let p = lib
             .join("workshop")
             .join("content")
             .join(APPID)
             .join(workshop_id);
-        if p.exists() {
-            let s = p.to_string_lossy().replace('/', "\\");
-            return Some(s);
+        // Steam can leave an empty stub behind after an unsubscribe; keep looking in that case.
+        let has_files = fs::read_dir(&p)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false);
+        if has_files {
+            return Some(native_path_string(&p));
         }
     }
     None
 }
 
+fn find_workshop_items(libs: &[PathBuf], workshop_ids: &[String]) -> Vec<WorkshopItemStatus> {
+    workshop_ids
+        .iter()
+        .map(|id| {
+            let path = find_workshop_item(libs, id);
+            WorkshopItemStatus {
+                id: id.clone(),
+                found: path.is_some(),
+                path,
+            }
+        })
+        .collect()
+}
+
+// Steam can leave a truncated manifest behind after crashing mid-write; existence alone isn't enough.
+fn read_app_manifest(path: &Path) -> Result<AppManifestInfo, LauncherError> {
+    let txt = fs::read_to_string(path)?;
+    if !txt.contains("\"AppState\"") {
+        return Err("missing AppState block".into());
+    }
+    let kv_re = Regex::new(r#""(\w+)"\s*"([^"]*)""#).unwrap();
+    let mut appid = None;
+    let mut installdir = None;
+    let mut last_owner = None;
+    let mut state_flags = None;
+    for cap in kv_re.captures_iter(&txt) {
+        match cap[1].to_ascii_lowercase().as_str() {
+            "appid" if appid.is_none() => appid = Some(cap[2].to_string()),
+            "installdir" if installdir.is_none() => installdir = Some(cap[2].to_string()),
+            "lastowner" if last_owner.is_none() => last_owner = Some(cap[2].to_string()),
+            "stateflags" if state_flags.is_none() => state_flags = cap[2].parse().ok(),
+            _ => {}
+        }
+    }
+    if appid.as_deref() != Some(APPID) {
+        return Err("appid is missing or wrong".into());
+    }
+    match installdir {
+        Some(dir) if !dir.trim().is_empty() => Ok(AppManifestInfo {
+            installdir: dir,
+            last_owner: last_owner.filter(|o| o != "0"),
+            state_flags,
+        }),
+        _ => Err("installdir is missing".into()),
+    }
+}
+
 #[tauri::command]
-fn auto_detect(workshop_id: String) -> DetectResp {
-    let steam_root =
-        steam_root_from_registry().unwrap_or_else(|| "C:/Program Files (x86)/Steam".to_string());
+// The first ID is the main pack; the rest are dependency mods that only need to be subscribed.
+fn auto_detect(workshop_ids: Vec<String>) -> Result<DetectResp, LauncherError> {
+    if workshop_ids.is_empty() {
+        return Err("No workshop IDs given".into());
+    }
+    let workshop_ids = normalize_workshop_ids(&workshop_ids)?;
+    let steam_found = detect_steam_root().is_some();
+    let steam_root = steam_root();
+    log::info!(
+        "auto_detect: workshop_ids={:?} steam_root={}",
+        workshop_ids,
+        steam_root
+    );
+    let libs = parse_libraryfolders(&steam_root);
+    let workshop_items = find_workshop_items(&libs, &workshop_ids);
     // Check if PZ is installed by looking for the app manifest
     let mut pz_installed = false;
+    let mut install_issue = None;
     let mut workshop_path = String::new();
-    for lib in parse_libraryfolders(&steam_root) {
+    for lib in &libs {
         let manifest = lib.join("appmanifest_108600.acf");
-        if manifest.exists() {
-            pz_installed = true;
-            // Also try to find the workshop path if possible
-            if let Some(wp) = find_workshop_item(&steam_root, &workshop_id) {
-                workshop_path = wp.replace('/', "\\");
+        if !manifest.exists() {
+            continue;
+        }
+        match read_app_manifest(&manifest) {
+            Ok(info) if lib.join("common").join(&info.installdir).exists() => {}
+            Ok(info) => {
+                install_issue = Some(format!(
+                    "Project Zomboid's install folder \"{}\" is missing. Verify the game files in Steam.",
+                    info.installdir
+                ));
+                continue;
+            }
+            Err(err) => {
+                install_issue = Some(format!(
+                    "Steam's install record for Project Zomboid looks corrupt ({}). Verify the game files in Steam.",
+                    err
+                ));
+                continue;
             }
-            break;
         }
+        pz_installed = true;
+        install_issue = None;
+        // Also try to find the workshop path if possible
+        if let Some(wp) = &workshop_items[0].path {
+            workshop_path = native_path_string(Path::new(wp));
+        }
+        break;
     }
-    if !pz_installed {
-        // Not installed, don't launch Steam or open workshop
-        return DetectResp {
-            steam_root,
-            workshop_path,
-        };
-    }
-    DetectResp {
+    // Windows is always case-insensitive; Proton/Linux libraries may not be
+    let case_warning = if cfg!(windows) || workshop_path.is_empty() {
+        None
+    } else {
+        match fs_case_sensitive(Path::new(&workshop_path)) {
+            Ok(true) => Some(
+                "Steam content is on a case-sensitive filesystem; mods with mismatched file name casing may fail to load."
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    };
+    let pz_ready = pz_installed && pz_install_complete(&libs);
+    Ok(DetectResp {
         steam_root,
+        steam_found,
+        pz_ready,
         workshop_path,
-    }
+        pz_installed,
+        install_issue,
+        case_warning,
+        workshop_items,
+    })
 }
 
 #[tauri::command]
-fn open_workshop(workshop_id: String) -> Result<(), String> {
-    let url = format!("steam://url/CommunityFilePage/{}", workshop_id);
-    open::that(url).map_err(|e| e.to_string())
+fn open_workshop(workshop_id: String) -> Result<(), LauncherError> {
+    let workshop_id = normalize_workshop_id(&workshop_id)?;
+    open_steam_url(format!("url/CommunityFilePage/{}", workshop_id))
 }
 
+const STEAM_URL_PATHS: &[&str] = &["open/console", "open/settings", "open/downloads"];
+
+// Only known steam:// pages, so the frontend can't hand arbitrary protocol URLs to the OS.
 #[tauri::command]
-fn open_path(path: String) -> Result<(), String> {
-    if path.is_empty() {
-        return Err("Empty path".into());
+fn open_steam_url(path: String) -> Result<(), LauncherError> {
+    let path = path.trim_start_matches("steam://");
+    let allowed = STEAM_URL_PATHS.contains(&path)
+        || path
+            .strip_prefix("url/CommunityFilePage/")
+            .is_some_and(|id| validate_workshop_id(id).is_ok());
+    if !allowed {
+        return Err(format!("steam://{} is not an allowed Steam URL", path).into());
     }
-    open::that(path).map_err(|e| e.to_string())
+    open::that(format!("steam://{}", path)).map_err(LauncherError::from)
 }
 
-fn workshop_zomboid_root(real_workshop_path: &Path) -> PathBuf {
-    real_workshop_path
-        .join("mods")
-        .join("13thPandemic")
-        .join("Zomboid")
+#[tauri::command]
+fn open_steam_console() -> Result<(), LauncherError> {
+    open_steam_url("open/console".into())
 }
 
-fn ping_host(host: &str) -> Option<u64> {
-    let output = Command::new("ping")
-        .arg("-n")
-        .arg("1")
-        .arg("-w")
-        .arg("1000")
-        .arg(host)
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
+// Opens the Steam page of every item that isn't downloaded yet and returns their IDs.
+#[tauri::command]
+fn open_missing_workshop_items(workshop_ids: Vec<String>) -> Result<Vec<String>, LauncherError> {
+    let workshop_ids = normalize_workshop_ids(&workshop_ids)?;
+    let missing: Vec<String> =
+        find_workshop_items(&parse_libraryfolders(&steam_root()), &workshop_ids)
+            .into_iter()
+            .filter(|item| !item.found)
+            .map(|item| item.id)
+            .collect();
+    for id in &missing {
+        open_workshop(id.clone())?;
     }
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let re = Regex::new(r"time[=<]\s*(\d+)\s*ms").ok()?;
-    let caps = re.captures(&stdout)?;
-    let value = caps.get(1)?.as_str().parse::<u64>().ok()?;
-    Some(value)
+    Ok(missing)
 }
 
-#[tauri::command]
-fn get_server_status(host: String) -> Result<ServerStatus, String> {
-    let host = host.trim();
-    if host.is_empty() {
-        return Err("Host is empty".into());
+// Returns the child item IDs when `workshop_id` is a collection, `None` when it is a plain item.
+fn fetch_collection_children(workshop_id: &str) -> Result<Option<Vec<String>>, LauncherError> {
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(NET_TIMEOUT)
+        .timeout(NET_TIMEOUT)
+        .build()?;
+    let body: serde_json::Value = client
+        .post(STEAM_COLLECTION_API)
+        .form(&[
+            ("collectioncount", "1"),
+            ("publishedfileids[0]", workshop_id),
+        ])
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| format!("Steam collection lookup failed: {}", e))?;
+    let details = &body["response"]["collectiondetails"][0];
+    if details["result"].as_u64() != Some(1) {
+        return Ok(None);
     }
-    let mut addrs = (host, 0)
-        .to_socket_addrs()
-        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?;
-    let ip = addrs
-        .next()
-        .ok_or_else(|| format!("No IP address found for {}", host))?
-        .ip()
-        .to_string();
-    let ping_ms = ping_host(host);
-    Ok(ServerStatus { ip, ping_ms })
+    let children: Vec<String> = details["children"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|c| c["publishedfileid"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    if children.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(children))
 }
 
-fn launcher_root(real_workshop_path: &Path) -> PathBuf {
-    real_workshop_path
-        .join("mods")
-        .join("13thPandemic")
-        .join("Launcher")
+// Steam keeps no local record of a collection's children, so the last answer from the API is
+// cached next to the settings and the network is only used when there isn't one.
+fn collection_cache_path(app_handle: &tauri::AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join("collections.json"))
 }
 
-fn launcher_backup_root(real_workshop_path: &Path) -> PathBuf {
-    launcher_root(real_workshop_path)
-        .join("backup")
-        .join("ProjectZomboid")
+fn read_collection_cache(app_handle: &tauri::AppHandle) -> HashMap<String, Vec<String>> {
+    collection_cache_path(app_handle)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
 }
 
-fn optimization_manifest_path(real_workshop_path: &Path) -> PathBuf {
-    launcher_root(real_workshop_path).join("optimizations.json")
+fn collection_children(
+    app_handle: &tauri::AppHandle,
+    workshop_id: &str,
+) -> Result<Vec<String>, LauncherError> {
+    let mut cache = read_collection_cache(app_handle);
+    if let Some(children) = cache.get(workshop_id).filter(|c| !c.is_empty()) {
+        return Ok(children.clone());
+    }
+    let children = fetch_collection_children(workshop_id)?.unwrap_or_default();
+    if children.is_empty() {
+        return Ok(children);
+    }
+    if let Some(path) = collection_cache_path(app_handle) {
+        cache.insert(workshop_id.to_string(), children.clone());
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, serde_json::to_string_pretty(&cache)?);
+    }
+    Ok(children)
 }
 
-fn launcher_log_path(real_workshop_path: &Path) -> PathBuf {
-    launcher_root(real_workshop_path).join("debug.txt")
+// Commands that touch the network hand the work to a blocking thread, so a slow or dead host
+// never holds up the IPC thread.
+#[tauri::command]
+async fn detect_workshop_collection(
+    app_handle: tauri::AppHandle,
+    workshop_id: String,
+) -> Result<CollectionCheck, LauncherError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        run_detect_workshop_collection(app_handle, workshop_id)
+    })
+    .await
+    .map_err(|e| LauncherError::Other(e.to_string()))?
 }
 
-fn pz_install_dir(steam_root: &str) -> Option<PathBuf> {
-    for lib in parse_libraryfolders(steam_root) {
-        let p = lib.join("common").join("ProjectZomboid");
-        if p.exists() {
-            return Some(p);
-        }
+fn run_detect_workshop_collection(
+    app_handle: tauri::AppHandle,
+    workshop_id: String,
+) -> Result<CollectionCheck, LauncherError> {
+    let workshop_id = normalize_workshop_id(&workshop_id)?;
+    let libs = parse_libraryfolders(&steam_root());
+    if find_workshop_item(&libs, &workshop_id).is_some() {
+        return Ok(CollectionCheck {
+            workshop_id,
+            content_found: true,
+            is_collection: false,
+            children: Vec::new(),
+            missing_children: Vec::new(),
+        });
     }
-    None
+    // Subscribing to a collection never creates a content folder for the collection ID itself.
+    let children = collection_children(&app_handle, &workshop_id)?;
+    let missing_children = children
+        .iter()
+        .filter(|id| find_workshop_item(&libs, id).is_none())
+        .cloned()
+        .collect();
+    Ok(CollectionCheck {
+        workshop_id,
+        content_found: false,
+        is_collection: !children.is_empty(),
+        children,
+        missing_children,
+    })
 }
 
-fn list_files_recursive(root: &Path) -> io::Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
-    let mut stack = vec![root.to_path_buf()];
-    while let Some(dir) = stack.pop() {
-        for ent in fs::read_dir(&dir)? {
-            let ent = ent?;
-            let p = ent.path();
-            if p.is_dir() {
-                stack.push(p);
-            } else {
-                files.push(p);
-            }
-        }
+fn workshop_content_dirs(libs: &[PathBuf], workshop_id: &str) -> Vec<PathBuf> {
+    if validate_workshop_id(workshop_id).is_err() {
+        return Vec::new();
     }
-    Ok(files)
+    libs.iter()
+        .map(|lib| {
+            lib.join("workshop")
+                .join("content")
+                .join(APPID)
+                .join(workshop_id)
+        })
+        .filter(|p| p.is_dir())
+        .collect()
 }
 
-fn file_sha256(path: &Path) -> io::Result<String> {
-    let mut file = fs::File::open(path)?;
-    let mut hasher = Sha256::new();
-    let mut buffer = [0u8; 8192];
-    loop {
-        let read = file.read(&mut buffer)?;
-        if read == 0 {
-            break;
+// A bad current_id is an error rather than "exclude nothing", which would offer the live pack
+// up for deletion.
+fn stale_packs(
+    libs: &[PathBuf],
+    current_id: &str,
+    old_ids: &[String],
+) -> Result<Vec<StalePack>, LauncherError> {
+    let mut packs = Vec::new();
+    let current_id = normalize_workshop_id(current_id)?;
+    for id in old_ids
+        .iter()
+        .filter_map(|id| normalize_workshop_id(id).ok())
+    {
+        if current_id == id {
+            continue;
+        }
+        for dir in workshop_content_dirs(libs, &id) {
+            packs.push(StalePack {
+                id: id.clone(),
+                bytes: dir_size(&dir).unwrap_or(0),
+                path: dir.to_string_lossy().to_string(),
+            });
         }
-        hasher.update(&buffer[..read]);
     }
-    let digest = hasher.finalize();
-    let mut hex = String::with_capacity(64);
-    for byte in digest {
-        hex.push_str(&format!("{:02x}", byte));
+    Ok(packs)
+}
+
+#[tauri::command]
+fn detect_stale_pack(
+    current_id: String,
+    old_ids: Vec<String>,
+) -> Result<Vec<StalePack>, LauncherError> {
+    let libs = parse_libraryfolders(&steam_root());
+    stale_packs(&libs, &current_id, &old_ids)
+}
+
+#[tauri::command]
+fn clean_stale_pack(
+    current_id: String,
+    old_ids: Vec<String>,
+) -> Result<Vec<StalePack>, LauncherError> {
+    let libs = parse_libraryfolders(&steam_root());
+    let packs = stale_packs(&libs, &current_id, &old_ids)?;
+    for pack in &packs {
+        fs::remove_dir_all(&pack.path)
+            .map_err(|e| format!("Failed to remove {}: {}", pack.path, e))?;
     }
-    Ok(hex)
+    Ok(packs)
 }
 
-fn build_manifest(root: &Path) -> io::Result<Vec<ManifestEntry>> {
-    let mut files = list_files_recursive(root)?;
-    files.sort();
-    let mut entries = Vec::with_capacity(files.len());
-    for path in files {
-        let rel = path
-            .strip_prefix(root)
-            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Invalid manifest path"))?;
-        let rel_str = rel.to_string_lossy().replace('\\', "/");
-        let size = fs::metadata(&path)?.len();
-        let hash = file_sha256(&path)?;
-        entries.push(ManifestEntry {
-            path: rel_str,
-            size,
-            hash,
-        });
+#[tauri::command]
+fn find_duplicate_workshop_content(
+    workshop_id: String,
+    steam_root: Option<String>,
+) -> Result<Vec<String>, LauncherError> {
+    let workshop_id = normalize_workshop_id(&workshop_id)?;
+    let steam_root = steam_root
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(crate::steam_root);
+    let copies = workshop_content_dirs(&parse_libraryfolders(&steam_root), &workshop_id);
+    if copies.len() < 2 {
+        return Ok(Vec::new());
     }
-    Ok(entries)
+    Ok(copies
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
 }
 
-fn write_manifest(path: &Path, entries: &[ManifestEntry]) -> io::Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+#[tauri::command]
+fn clean_duplicate_workshop(
+    workshop_id: String,
+    keep_path: String,
+) -> Result<Vec<String>, LauncherError> {
+    let copies = find_duplicate_workshop_content(workshop_id, None)?;
+    let keep = PathBuf::from(&keep_path);
+    if !copies.iter().any(|c| Path::new(c) == keep) {
+        return Err(format!("{} is not one of the workshop copies", keep_path).into());
     }
-    let manifest = OptimizationManifest {
-        entries: entries.to_vec(),
-    };
-    let json =
-        serde_json::to_string_pretty(&manifest).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-    fs::write(path, json)?;
-    Ok(())
+    let mut removed = Vec::new();
+    for copy in copies.into_iter().filter(|c| Path::new(c) != keep) {
+        fs::remove_dir_all(&copy).map_err(|e| format!("Failed to remove {}: {}", copy, e))?;
+        removed.push(copy);
+    }
+    Ok(removed)
 }
 
-fn read_manifest(path: &Path) -> io::Result<OptimizationManifest> {
-    let raw = fs::read_to_string(path)?;
-    serde_json::from_str(&raw).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+#[tauri::command]
+fn list_servers() -> Vec<ServerProfile> {
+    match serde_json::from_str::<Vec<ServerProfile>>(include_str!("../servers.json")) {
+        Ok(servers) if !servers.is_empty() => servers,
+        _ => vec![ServerProfile::default()],
+    }
 }
 
-fn manifest_matches_dest(entries: &[ManifestEntry], dst_root: &Path) -> io::Result<bool> {
-    if entries.is_empty() {
-        return Ok(false);
+// Sends A2S_INFO, answering a challenge if the server asks for one, and returns the latency of
+// the first reply along with the final response packet.
+fn a2s_info_exchange(host: &str, port: u16, timeout: Duration) -> io::Result<(Duration, Vec<u8>)> {
+    let addr = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no address for server"))?;
+    let socket = UdpSocket::bind(if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    })?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.set_write_timeout(Some(timeout))?;
+    socket.connect(addr)?;
+    let mut request = b"\xFF\xFF\xFF\xFFTSource Engine Query\0".to_vec();
+    let started = Instant::now();
+    socket.send(&request)?;
+    let mut buf = [0u8; 1400];
+    let mut len = socket.recv(&mut buf)?;
+    let latency = started.elapsed();
+    if len >= 9 && buf[4] == 0x41 {
+        request.extend_from_slice(&buf[5..9]);
+        socket.send(&request)?;
+        len = socket.recv(&mut buf)?;
     }
-    for entry in entries {
-        let dest_path = dst_root.join(Path::new(&entry.path));
-        let meta = match fs::metadata(&dest_path) {
-            Ok(m) => m,
-            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
-            Err(err) => return Err(err),
-        };
-        if meta.len() != entry.size {
-            return Ok(false);
-        }
-        let hash = file_sha256(&dest_path)?;
-        if hash != entry.hash {
-            return Ok(false);
-        }
+    Ok((latency, buf[..len].to_vec()))
+}
+
+// Read timeouts surface as WouldBlock on Windows and TimedOut elsewhere.
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+    )
+}
+
+fn query_server_info(host: &str, port: u16, timeout: Duration) -> io::Result<Duration> {
+    a2s_info_exchange(host, port, timeout).map(|(latency, _)| latency)
+}
+
+fn read_cstring(data: &[u8], pos: &mut usize) -> Option<String> {
+    let rest = data.get(*pos..)?;
+    let end = rest.iter().position(|&b| b == 0)?;
+    *pos += end + 1;
+    Some(String::from_utf8_lossy(&rest[..end]).to_string())
+}
+
+// A2S_INFO reply: header, protocol, name, map, folder, game, app id, players, max players.
+fn parse_a2s_info(data: &[u8]) -> Option<ServerInfo> {
+    if data.get(..5)? != b"\xFF\xFF\xFF\xFFI" {
+        return None;
     }
-    Ok(true)
+    let mut pos = 6;
+    let name = read_cstring(data, &mut pos)?;
+    let map = read_cstring(data, &mut pos)?;
+    read_cstring(data, &mut pos)?;
+    read_cstring(data, &mut pos)?;
+    pos += 2;
+    Some(ServerInfo {
+        name,
+        map,
+        players: *data.get(pos)?,
+        max_players: *data.get(pos + 1)?,
+    })
 }
 
-fn manifest_matches_src(entries: &[ManifestEntry], src_root: &Path) -> io::Result<bool> {
-    if entries.is_empty() {
-        return Ok(false);
+#[tauri::command]
+async fn server_info(query_port: Option<u16>) -> Option<ServerInfo> {
+    let port = query_port.unwrap_or(SERVER_QUERY_PORT);
+    let (_, response) = tauri::async_runtime::spawn_blocking(move || {
+        a2s_info_exchange(SERVER_IP, port, NET_TIMEOUT)
+    })
+    .await
+    .ok()?
+    .ok()?;
+    parse_a2s_info(&response)
+}
+
+#[tauri::command]
+async fn server_status() -> ServerReachability {
+    // The UDP exchange blocks for up to NET_TIMEOUT, so it stays off the async workers.
+    let result = tauri::async_runtime::spawn_blocking(|| {
+        query_server_info(SERVER_IP, SERVER_PORT, NET_TIMEOUT)
+    })
+    .await;
+    match result {
+        Ok(Ok(latency)) => ServerReachability {
+            reachable: true,
+            latency_ms: Some(latency.as_millis() as u64),
+            timed_out: false,
+        },
+        Ok(Err(err)) => ServerReachability {
+            reachable: false,
+            latency_ms: None,
+            timed_out: is_timeout(&err),
+        },
+        Err(_) => ServerReachability {
+            reachable: false,
+            latency_ms: None,
+            timed_out: false,
+        },
     }
-    let src_files = list_files_recursive(src_root)?;
-    if src_files.len() != entries.len() {
-        return Ok(false);
+}
+
+// Steam only reports an item complete once its installed manifest matches the latest one.
+fn workshop_item_state(libs: &[PathBuf], workshop_id: &str) -> &'static str {
+    if validate_workshop_id(workshop_id).is_err() {
+        return "missing";
     }
-    for entry in entries {
-        let src_path = src_root.join(Path::new(&entry.path));
-        let meta = match fs::metadata(&src_path) {
-            Ok(m) => m,
-            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
-            Err(err) => return Err(err),
+    let mut state = "missing";
+    for lib in libs {
+        let workshop = lib.join("workshop");
+        if workshop
+            .join("downloads")
+            .join(APPID)
+            .join(workshop_id)
+            .exists()
+        {
+            return "downloading";
+        }
+        let content_exists = workshop
+            .join("content")
+            .join(APPID)
+            .join(workshop_id)
+            .is_dir();
+        let acf = match fs::read_to_string(workshop.join(format!("appworkshop_{}.acf", APPID))) {
+            Ok(txt) => txt,
+            Err(_) => {
+                if content_exists {
+                    state = "complete";
+                }
+                continue;
+            }
         };
-        if meta.len() != entry.size {
-            return Ok(false);
+        let installed = vdf_blocks(&acf, "WorkshopItemsInstalled")
+            .into_iter()
+            .flat_map(|block| vdf_blocks(block, workshop_id))
+            .next();
+        let details = vdf_blocks(&acf, "WorkshopItemDetails")
+            .into_iter()
+            .flat_map(|block| vdf_blocks(block, workshop_id))
+            .next();
+        match (installed, details) {
+            (Some(installed), details) => {
+                let manifest = vdf_value(installed, "manifest");
+                let latest = details.and_then(|d| vdf_value(d, "latest_manifest"));
+                if latest.is_some_and(|latest| manifest.as_deref() != Some(latest.as_str())) {
+                    return "downloading";
+                }
+                if content_exists {
+                    state = "complete";
+                }
+            }
+            // Subscribed but nothing installed yet
+            (None, Some(_)) => return "downloading",
+            (None, None) if content_exists => state = "complete",
+            (None, None) => {}
         }
     }
-    Ok(true)
+    state
 }
 
-fn optimizations_applied(
-    src_root: &Path,
-    dst_root: &Path,
-    manifest_path: &Path,
-) -> io::Result<bool> {
-    if !dst_root.exists() {
-        return Ok(false);
+#[tauri::command]
+fn workshop_download_state(workshop_id: String) -> String {
+    match normalize_workshop_id(&workshop_id) {
+        Ok(id) => workshop_item_state(&parse_libraryfolders(&steam_root()), &id).to_string(),
+        Err(_) => "missing".to_string(),
     }
-    if manifest_path.exists() {
-        let manifest = read_manifest(manifest_path)?;
-        if manifest_matches_src(&manifest.entries, src_root)? {
-            return manifest_matches_dest(&manifest.entries, dst_root);
+}
+
+// Opens the item's Steam page and reports download progress until Steam finishes installing it.
+#[tauri::command]
+fn subscribe_and_wait(
+    app_handle: tauri::AppHandle,
+    workshop_id: String,
+) -> Result<(), LauncherError> {
+    let workshop_id = normalize_workshop_id(&workshop_id)?;
+    open_workshop(workshop_id.clone())?;
+    thread::spawn(move || {
+        let started = Instant::now();
+        while started.elapsed() < SUBSCRIBE_WAIT_TIMEOUT {
+            // Re-read every poll; Steam may add a library while the download is running.
+            let libs = parse_libraryfolders(&steam_root());
+            let state = workshop_item_state(&libs, &workshop_id);
+            if state == "complete" {
+                if let Some(path) = find_workshop_item(&libs, &workshop_id) {
+                    log::info!("subscribe_and_wait: {} ready at {}", workshop_id, path);
+                    let _ = app_handle.emit(
+                        "workshop-ready",
+                        serde_json::json!({ "workshop_id": workshop_id, "path": path }),
+                    );
+                    return;
+                }
+            }
+            let _ = app_handle.emit(
+                "workshop-subscribe-progress",
+                serde_json::json!({
+                    "workshop_id": workshop_id,
+                    "state": state,
+                    "elapsed_ms": started.elapsed().as_millis() as u64,
+                }),
+            );
+            thread::sleep(WORKSHOP_POLL_INTERVAL);
         }
-        let entries = build_manifest(src_root)?;
-        let matches = manifest_matches_dest(&entries, dst_root)?;
-        if matches {
-            write_manifest(manifest_path, &entries)?;
+        let _ = app_handle.emit(
+            "workshop-subscribe-timeout",
+            serde_json::json!({ "workshop_id": workshop_id }),
+        );
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn cancel_session_watch(session_watch: tauri::State<'_, SessionWatch>) {
+    session_watch.cancel();
+}
+
+#[tauri::command]
+fn open_path(path: String) -> Result<(), LauncherError> {
+    if path.is_empty() {
+        return Err("Empty path".into());
+    }
+    open::that(path).map_err(LauncherError::from)
+}
+
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+fn parse_loginusers(steam_root: &str) -> Vec<SteamLoginUser> {
+    let vdf = Path::new(steam_root).join("config").join("loginusers.vdf");
+    let txt = match fs::read_to_string(&vdf) {
+        Ok(t) => t,
+        Err(_) => return Vec::new(),
+    };
+    let block_re = Regex::new(r#""(\d{17})"\s*\{([^}]*)\}"#).unwrap();
+    let kv_re = Regex::new(r#""(\w+)"\s*"([^"]*)""#).unwrap();
+    let mut users = Vec::new();
+    for cap in block_re.captures_iter(&txt) {
+        let mut user = SteamLoginUser {
+            steam_id: cap[1].to_string(),
+            account_name: String::new(),
+            persona_name: String::new(),
+            most_recent: false,
+            remember_password: false,
+        };
+        for kv in kv_re.captures_iter(&cap[2]) {
+            match kv[1].to_ascii_lowercase().as_str() {
+                "accountname" => user.account_name = kv[2].to_string(),
+                "personaname" => user.persona_name = kv[2].to_string(),
+                "mostrecent" => user.most_recent = &kv[2] == "1",
+                "rememberpassword" => user.remember_password = &kv[2] == "1",
+                _ => {}
+            }
         }
-        return Ok(matches);
+        users.push(user);
     }
-    let entries = build_manifest(src_root)?;
-    let matches = manifest_matches_dest(&entries, dst_root)?;
-    if matches {
-        write_manifest(manifest_path, &entries)?;
+    users
+}
+
+fn steam_account_id(steam_id64: &str) -> Option<u64> {
+    steam_id64.parse::<u64>().ok()?.checked_sub(STEAM_ID64_BASE)
+}
+
+// Steam has no offline license list per account, so treat PZ as owned when the account's
+// userdata has a 108600 folder or its localconfig tracks the app.
+fn account_has_pz(steam_root: &str, steam_id64: &str) -> bool {
+    let account_id = match steam_account_id(steam_id64) {
+        Some(id) => id,
+        None => return false,
+    };
+    let userdata = Path::new(steam_root)
+        .join("userdata")
+        .join(account_id.to_string());
+    if userdata.join(APPID).exists() {
+        return true;
+    }
+    let localconfig = userdata.join("config").join("localconfig.vdf");
+    match fs::read_to_string(localconfig) {
+        Ok(txt) => Regex::new(&format!(r#""{}"\s*\{{"#, APPID))
+            .map(|re| re.is_match(&txt))
+            .unwrap_or(false),
+        Err(_) => false,
     }
-    Ok(matches)
 }
 
-fn copy_dir_replace(
-    src_root: &Path,
-    dst_root: &Path,
-    backup_root: Option<&Path>,
-) -> io::Result<(u64, u64, u64)> {
-    let mut copied: u64 = 0;
-    let mut replaced: u64 = 0;
-    let mut backed_up: u64 = 0;
-    for s in list_files_recursive(src_root)? {
-        let rel = s.strip_prefix(src_root).unwrap();
-        let d = dst_root.join(rel);
-        if let Some(parent) = d.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        if d.exists() {
-            if let Some(backup_root) = backup_root {
-                let backup_path = backup_root.join(rel);
-                if !backup_path.exists() {
-                    if let Some(parent) = backup_path.parent() {
-                        fs::create_dir_all(parent)?;
+// Returns the bodies of every `"key" { ... }` block, honouring quoted braces.
+fn vdf_blocks<'a>(txt: &'a str, key: &str) -> Vec<&'a str> {
+    let re = match Regex::new(&format!(r#""{}"\s*\{{"#, regex::escape(key))) {
+        Ok(re) => re,
+        Err(_) => return Vec::new(),
+    };
+    let mut blocks = Vec::new();
+    for m in re.find_iter(txt) {
+        let body_start = m.end();
+        let mut depth = 1;
+        let mut in_quotes = false;
+        let mut escaped = false;
+        for (offset, ch) in txt[body_start..].char_indices() {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' if in_quotes => escaped = true,
+                '"' => in_quotes = !in_quotes,
+                '{' if !in_quotes => depth += 1,
+                '}' if !in_quotes => {
+                    depth -= 1;
+                    if depth == 0 {
+                        blocks.push(&txt[body_start..body_start + offset]);
+                        break;
                     }
-                    fs::copy(&d, &backup_path)?;
-                    backed_up += 1;
                 }
+                _ => {}
             }
-            fs::copy(&s, &d)?;
-            replaced += 1;
-        } else {
-            fs::copy(&s, &d)?;
-            copied += 1;
         }
     }
-    Ok((copied, replaced, backed_up))
+    blocks
 }
 
-#[tauri::command]
-fn resolve_game_root() -> Result<String, String> {
-    let steam_root =
-        steam_root_from_registry().unwrap_or_else(|| "C:/Program Files (x86)/Steam".to_string());
-    let p = pz_install_dir(&steam_root)
-        .ok_or_else(|| "Project Zomboid install not found".to_string())?;
-    Ok(p.to_string_lossy().to_string())
+fn vdf_value(block: &str, key: &str) -> Option<String> {
+    let re = Regex::new(&format!(r#""{}"\s*"([^"]*)""#, regex::escape(key))).ok()?;
+    re.captures(block).map(|cap| cap[1].to_string())
+}
+
+fn vdf_unescape(value: &str) -> String {
+    value.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+fn localconfig_path(steam_root: &str, steam_id64: &str) -> Option<PathBuf> {
+    let account_id = steam_account_id(steam_id64)?;
+    Some(
+        Path::new(steam_root)
+            .join("userdata")
+            .join(account_id.to_string())
+            .join("config")
+            .join("localconfig.vdf"),
+    )
+}
+
+fn pz_launch_options(localconfig: &Path) -> Option<String> {
+    let txt = fs::read_to_string(localconfig).ok()?;
+    let re = Regex::new(r#""LaunchOptions"\s*"((?:[^"\\]|\\.)*)""#).ok()?;
+    vdf_blocks(&txt, APPID)
+        .into_iter()
+        .find_map(|block| re.captures(block).map(|cap| vdf_unescape(&cap[1])))
 }
 
 #[tauri::command]
-fn apply_optimizations(workshop_path: String) -> Result<serde_json::Value, String> {
+fn persist_launch_args_check(
+    monitor: tauri::State<'_, ProcessMonitor>,
+    workshop_path: String,
+) -> Result<LaunchArgsCheck, LauncherError> {
     if workshop_path.is_empty() {
-        return Err("Workshop path is empty".into());
-    }
-    let steam_root =
-        steam_root_from_registry().unwrap_or_else(|| "C:/Program Files (x86)/Steam".to_string());
-    // Source: <workshop>\mods\13thPandemic\ProjectZomboid
-    let src = Path::new(&workshop_path)
-        .join("mods")
-        .join("13thPandemic")
-        .join("ProjectZomboid");
-    if !src.exists() {
-        return Err(format!("Optimizations folder not found: {}", src.display()));
-    }
-    let dest = pz_install_dir(&steam_root)
-        .ok_or_else(|| "Could not locate ProjectZomboid install directory".to_string())?;
-    let manifest_path = optimization_manifest_path(Path::new(&workshop_path));
-
-    if optimizations_applied(&src, &dest, &manifest_path).map_err(|e| e.to_string())? {
-        return Ok(serde_json::json!({
-          "already": true,
-          "applied": false,
-          "source": src.to_string_lossy().to_string(),
-          "dest": dest.to_string_lossy().to_string(),
-          "manifest": manifest_path.to_string_lossy().to_string()
-        }));
+        return Err(LauncherError::WorkshopPathEmpty);
     }
+    let steam_root = steam_root();
+    let expected_cachedir = native_path_string(&workshop_zomboid_root(Path::new(&workshop_path)));
+    let localconfig = parse_loginusers(&steam_root)
+        .iter()
+        .find(|user| user.most_recent)
+        .and_then(|user| localconfig_path(&steam_root, &user.steam_id));
+    // Read back what Steam actually kept on disk rather than what was last written.
+    let launch_options = localconfig.as_deref().and_then(pz_launch_options);
+    let cachedir_present = launch_options
+        .as_deref()
+        .map(|opts| opts.contains(&format!("-cachedir={}", expected_cachedir)))
+        .unwrap_or(false);
+    let steam_running = steam_is_running(&monitor.snapshot());
+    let warning = match (&localconfig, cachedir_present) {
+        (None, _) => Some("No Steam login found; launch options can't be checked.".to_string()),
+        (Some(_), true) => None,
+        (Some(_), false) if steam_running => Some(
+            "The -cachedir launch option is not in Steam's config. Steam rewrites localconfig.vdf on exit, so edits made while it runs are reverted.".to_string(),
+        ),
+        (Some(_), false) => Some(
+            "The -cachedir launch option is not in Steam's config; it did not persist.".to_string(),
+        ),
+    };
+    Ok(LaunchArgsCheck {
+        localconfig: localconfig.map(|p| p.to_string_lossy().to_string()),
+        launch_options,
+        expected_cachedir,
+        cachedir_present,
+        steam_running,
+        warning,
+    })
+}
 
-    let backup_root = launcher_backup_root(Path::new(&workshop_path));
-    fs::create_dir_all(&backup_root).map_err(|e| e.to_string())?;
-    let (copied, replaced, backed_up) =
-        copy_dir_replace(&src, &dest, Some(&backup_root)).map_err(|e| e.to_string())?;
-    let entries = build_manifest(&src).map_err(|e| e.to_string())?;
-    write_manifest(&manifest_path, &entries).map_err(|e| e.to_string())?;
-    Ok(serde_json::json!({
-      "already": false,
-      "applied": true,
-      "copied": copied,
-      "replaced": replaced,
-      "backed_up": backed_up,
-      "source": src.to_string_lossy().to_string(),
-      "dest": dest.to_string_lossy().to_string(),
-      "backup_root": backup_root.to_string_lossy().to_string(),
-      "manifest": manifest_path.to_string_lossy().to_string()
-    }))
+#[tauri::command]
+fn pz_ownership_by_account() -> Vec<AccountOwnership> {
+    let steam_root = steam_root();
+    parse_loginusers(&steam_root)
+        .into_iter()
+        .map(|user| AccountOwnership {
+            owns_pz: account_has_pz(&steam_root, &user.steam_id),
+            account: user.account_name,
+            persona: user.persona_name,
+            steam_id: user.steam_id,
+            most_recent: user.most_recent,
+        })
+        .collect()
 }
 
+// A borrowed copy installs under the lender's license; workshop items subscribed by the borrower
+// often never download in that state.
 #[tauri::command]
-fn check_optimizations(workshop_path: String) -> Result<bool, String> {
-    if workshop_path.is_empty() {
-        return Err("Workshop path is empty".into());
+fn family_share_workshop_warning() -> Option<String> {
+    let steam_root = steam_root();
+    let current = parse_loginusers(&steam_root)
+        .into_iter()
+        .find(|user| user.most_recent)?;
+    let owner = parse_libraryfolders(&steam_root)
+        .into_iter()
+        .map(|lib| lib.join("appmanifest_108600.acf"))
+        .filter(|manifest| manifest.exists())
+        .find_map(|manifest| read_app_manifest(&manifest).ok()?.last_owner)?;
+    if owner == current.steam_id {
+        return None;
     }
-    let steam_root =
-        steam_root_from_registry().unwrap_or_else(|| "C:/Program Files (x86)/Steam".to_string());
-    let src = Path::new(&workshop_path)
+    Some(format!(
+        "Project Zomboid on this PC is borrowed through Steam Family Sharing (licensed to another account, not {}). Workshop mods may never download for a borrowed copy; the player needs to own the game to use the modpack reliably.",
+        current.persona_name
+    ))
+}
+
+fn workshop_zomboid_root(real_workshop_path: &Path) -> PathBuf {
+    real_workshop_path
         .join("mods")
         .join("13thPandemic")
-        .join("ProjectZomboid");
-    if !src.exists() {
-        return Err(format!("Optimizations folder not found: {}", src.display()));
-    }
-    let dest = pz_install_dir(&steam_root)
-        .ok_or_else(|| "Could not locate ProjectZomboid install directory".to_string())?;
-    let manifest_path = optimization_manifest_path(Path::new(&workshop_path));
-    optimizations_applied(&src, &dest, &manifest_path).map_err(|e| e.to_string())
+        .join("Zomboid")
+}
+
+fn pz_logs_dir(real_workshop_path: &Path) -> PathBuf {
+    workshop_zomboid_root(real_workshop_path).join("Logs")
 }
 
 #[tauri::command]
-fn open_launcher_log(workshop_path: String) -> Result<String, String> {
+fn open_logs(workshop_path: String) -> Result<(), LauncherError> {
     if workshop_path.is_empty() {
-        return Err("Workshop path is empty".into());
+        return Err(LauncherError::WorkshopPathEmpty);
     }
-    let log_path = launcher_log_path(Path::new(&workshop_path));
-    if let Some(parent) = log_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    let logs = pz_logs_dir(Path::new(&workshop_path));
+    if !logs.is_dir() {
+        return Err(
+            "No game logs yet. Launch Project Zomboid once through the launcher to create them."
+                .into(),
+        );
     }
-    if !log_path.exists() {
-        fs::write(&log_path, "").map_err(|e| e.to_string())?;
+    open::that(&logs).map_err(|e| format!("Could not open {}: {}", logs.display(), e).into())
+}
+
+// Any folder on the way to cachedir/Mods may be a junction to another drive.
+fn first_junction(real_workshop_path: &Path) -> Option<PathBuf> {
+    let cachedir = workshop_zomboid_root(real_workshop_path);
+    [
+        real_workshop_path.join("mods"),
+        real_workshop_path.join("mods").join("13thPandemic"),
+        cachedir.clone(),
+        cachedir.join("Mods"),
+    ]
+    .into_iter()
+    .find(|path| fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink()))
+}
+
+// Canonical target of that junction; falls back to the raw link target when the drive is gone.
+fn resolve_mods_junction(workshop_path: &Path) -> Option<PathBuf> {
+    let link = first_junction(workshop_path)?;
+    fs::canonicalize(&link).ok().or_else(|| {
+        let target = fs::read_link(&link).ok()?;
+        match link.parent() {
+            Some(parent) if target.is_relative() => Some(parent.join(target)),
+            _ => Some(target),
+        }
+    })
+}
+
+fn cachedir_junction(real_workshop_path: &Path) -> CachedirJunction {
+    if let Some(path) = first_junction(real_workshop_path) {
+        return CachedirJunction {
+            is_junction: true,
+            path: path.to_string_lossy().to_string(),
+            target_available: path.is_dir(),
+            target: resolve_mods_junction(real_workshop_path)
+                .map(|t| t.to_string_lossy().to_string()),
+        };
     }
-    open::that(&log_path).map_err(|e| e.to_string())?;
-    Ok(log_path.to_string_lossy().to_string())
+    let mods = workshop_zomboid_root(real_workshop_path).join("Mods");
+    CachedirJunction {
+        is_junction: false,
+        target_available: mods.is_dir(),
+        path: mods.to_string_lossy().to_string(),
+        target: None,
+    }
+}
+
+// Cachedir subfolders holding compiled shaders; safe to delete, PZ rebuilds them on next start.
+const SHADER_CACHE_DIRS: &[&str] = &["shadercache"];
+
+fn dir_size(root: &Path) -> io::Result<u64> {
+    let mut total: u64 = 0;
+    for p in list_files_recursive(root)? {
+        total += fs::metadata(&p)?.len();
+    }
+    Ok(total)
+}
+
+fn shader_cache_dirs(real_workshop_path: &Path) -> Vec<PathBuf> {
+    let cachedir = workshop_zomboid_root(real_workshop_path);
+    SHADER_CACHE_DIRS
+        .iter()
+        .map(|name| cachedir.join(name))
+        .filter(|p| p.is_dir())
+        .collect()
 }
 
 #[tauri::command]
-fn append_launcher_log(workshop_path: String, entry: String) -> Result<(), String> {
+fn shader_cache_size(workshop_path: String) -> Result<u64, LauncherError> {
     if workshop_path.is_empty() {
-        return Err("Workshop path is empty".into());
+        return Err(LauncherError::WorkshopPathEmpty);
     }
-    let log_path = launcher_log_path(Path::new(&workshop_path));
-    if let Some(parent) = log_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    let mut total: u64 = 0;
+    for dir in shader_cache_dirs(Path::new(&workshop_path)) {
+        total += dir_size(&dir)?;
     }
-    let mut file = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)
-        .map_err(|e| e.to_string())?;
-    file.write_all(entry.as_bytes())
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(total)
 }
 
+// Only direct items of the cachedir may be removed; links are unlinked, never followed.
 #[tauri::command]
-fn write_launcher_log(workshop_path: String, contents: String) -> Result<(), String> {
+fn clean_cachedir(workshop_path: String, what: Vec<String>) -> Result<u64, LauncherError> {
     if workshop_path.is_empty() {
-        return Err("Workshop path is empty".into());
+        return Err(LauncherError::WorkshopPathEmpty);
     }
-    let log_path = launcher_log_path(Path::new(&workshop_path));
-    if let Some(parent) = log_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    let cachedir = workshop_zomboid_root(Path::new(&workshop_path));
+    let root = fs::canonicalize(&cachedir)?;
+    let mut freed: u64 = 0;
+    for item in &what {
+        let rel = safe_relative_path(item)
+            .filter(|rel| rel.components().count() == 1)
+            .ok_or_else(|| format!("Refusing to delete \"{}\" outside the cachedir", item))?;
+        let target = cachedir.join(rel);
+        let meta = match fs::symlink_metadata(&target) {
+            Ok(meta) => meta,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        if meta.file_type().is_symlink() {
+            fs::remove_file(&target)
+                .or_else(|_| fs::remove_dir(&target))
+                .map_err(|e| format!("Failed to remove {}: {}", target.display(), e))?;
+            continue;
+        }
+        if !fs::canonicalize(&target)?.starts_with(&root) {
+            return Err(format!(
+                "Refusing to delete {} outside the cachedir",
+                target.display()
+            )
+            .into());
+        }
+        let (bytes, removed) = if meta.is_dir() {
+            (dir_size(&target)?, fs::remove_dir_all(&target))
+        } else {
+            (meta.len(), fs::remove_file(&target))
+        };
+        removed.map_err(|e| format!("Failed to remove {}: {}", target.display(), e))?;
+        freed += bytes;
     }
-    fs::write(&log_path, contents).map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(freed)
 }
 
 #[tauri::command]
-fn play(
-    app_handle: tauri::AppHandle,
-    appid: String,
-    _workshop_id: String,
-    workshop_path: String,
-    extra_args: Option<Vec<String>>,
-) -> Result<(), String> {
+fn clear_shader_cache(workshop_path: String) -> Result<serde_json::Value, LauncherError> {
     if workshop_path.is_empty() {
-        return Err("Workshop path is empty".into());
+        return Err(LauncherError::WorkshopPathEmpty);
     }
-    // Ensure Steam is running before launching PZ
-    let steam_root =
-        steam_root_from_registry().unwrap_or_else(|| "C:/Program Files (x86)/Steam".to_string());
-    let mut sys = System::new_all();
-    sys.refresh_processes();
-    let steam_running = sys
-        .processes()
-        .values()
-        .any(|p| p.name().eq_ignore_ascii_case("steam.exe"));
-    if !steam_running {
-        let steam_exe = Path::new(&steam_root).join("steam.exe");
-        let _ = Command::new(&steam_exe).spawn();
-        // Give Steam a few seconds to start
-        thread::sleep(Duration::from_secs(3));
-    }
-    // Always point cachedir to the workshop Zomboid folder; Mods may be a junction to another drive
-    let cachedir = workshop_zomboid_root(Path::new(&workshop_path));
-    // Ensure the cachedir exists
-    fs::create_dir_all(&cachedir)
-        .map_err(|e| format!("Failed to create cachedir {}: {}", cachedir.display(), e))?;
-    let cachedir_windows = cachedir.to_string_lossy().replace('/', "\\");
-
-    // Launch Steam -> PZ with -cachedir and auto-connect using -applaunch
-    let steam_exe = Path::new(&steam_root).join("steam.exe");
-    let cachedir_arg = format!("-cachedir={}", cachedir_windows);
-    let mut command = Command::new(&steam_exe);
-    command
-        .arg("-applaunch")
-        .arg(appid)
-        .arg(&cachedir_arg)
-        .arg(format!("-connect={}", SERVER_IP))
-        .arg(format!("-port={}", SERVER_PORT));
-    if let Some(extra_args) = extra_args {
-        for arg in extra_args {
-            if !arg.trim().is_empty() {
-                command.arg(arg);
-            }
-        }
+    let mut freed: u64 = 0;
+    let mut removed = Vec::new();
+    for dir in shader_cache_dirs(Path::new(&workshop_path)) {
+        freed += dir_size(&dir)?;
+        fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to remove {}: {}", dir.display(), e))?;
+        removed.push(dir.to_string_lossy().to_string());
     }
-    command
-        .spawn()
-        .map_err(|e| format!("Failed to launch Steam/PZ: {}", e))?;
+    Ok(serde_json::json!({
+      "freed_bytes": freed,
+      "removed": removed
+    }))
+}
 
-    let launch_payload = serde_json::json!({ "cachedir": cachedir_windows.clone() });
-    let _ = app_handle.emit("pz-session-launched", launch_payload);
+#[cfg(windows)]
+fn gpu_vendor(name: &str) -> String {
+    let lower = name.to_ascii_lowercase();
+    let vendor =
+        if lower.contains("nvidia") || lower.contains("geforce") || lower.contains("quadro") {
+            "NVIDIA"
+        } else if lower.contains("amd") || lower.contains("radeon") {
+            "AMD"
+        } else if lower.contains("intel") {
+            "Intel"
+        } else {
+            "Unknown"
+        };
+    vendor.to_string()
+}
 
-    let handle_for_exit = app_handle.clone();
-    let cachedir_for_exit = cachedir_windows.clone();
-    thread::spawn(move || {
-        let mut watcher = System::new_all();
-        let proc_name = "ProjectZomboid64.exe";
-        let mut found = false;
-        for _ in 0..10 {
-            watcher.refresh_processes();
-            if watcher
-                .processes()
-                .values()
-                .any(|p| p.name().eq_ignore_ascii_case(proc_name))
-            {
-                found = true;
-                break;
-            }
-            thread::sleep(Duration::from_secs(1));
+fn gpu_is_integrated(gpu: &GpuInfo) -> bool {
+    let lower = gpu.name.to_ascii_lowercase();
+    (gpu.vendor == "Intel" && !lower.contains("arc"))
+        || lower.ends_with("radeon(tm) graphics")
+        || lower.ends_with("radeon graphics")
+        || Regex::new(r"vega \d+ graphics").is_ok_and(|re| re.is_match(&lower))
+}
+
+// Display adapters live under the device class key; qwMemorySize is the only VRAM value
+// that isn't capped at 4 GB.
+#[cfg(windows)]
+fn detect_gpus() -> Vec<GpuInfo> {
+    let class = match RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(DISPLAY_ADAPTER_CLASS) {
+        Ok(key) => key,
+        Err(_) => return Vec::new(),
+    };
+    let mut gpus: Vec<GpuInfo> = Vec::new();
+    for sub in class.enum_keys().filter_map(|k| k.ok()) {
+        let adapter = match class.open_subkey(&sub) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        let name = match adapter.get_value::<String, _>("DriverDesc") {
+            Ok(name) if !name.trim().is_empty() => name,
+            _ => continue,
+        };
+        let vram_bytes = adapter
+            .get_value::<u64, _>("HardwareInformation.qwMemorySize")
+            .or_else(|_| {
+                adapter
+                    .get_value::<u32, _>("HardwareInformation.MemorySize")
+                    .map(u64::from)
+            })
+            .unwrap_or(0);
+        if gpus.iter().any(|g| g.name == name) {
+            continue;
+        }
+        gpus.push(GpuInfo {
+            vendor: gpu_vendor(&name),
+            name,
+            vram_mb: vram_bytes / (1024 * 1024),
+        });
+    }
+    gpus
+}
+
+// `lspci -mm` prints quoted fields: slot "class" "vendor" "device" ...
+#[cfg(not(windows))]
+fn lspci_device_name(slot: &str) -> Option<String> {
+    let output = Command::new("lspci")
+        .args(["-mm", "-s", slot])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = text.split('"').skip(1).step_by(2).collect();
+    match (fields.get(1), fields.get(2)) {
+        (Some(vendor), Some(device)) => Some(format!("{} {}", vendor, device)),
+        _ => None,
+    }
+}
+
+// Each GPU shows up as /sys/class/drm/cardN; amdgpu is the only driver that reports VRAM there.
+#[cfg(not(windows))]
+fn detect_gpus() -> Vec<GpuInfo> {
+    let cards = match fs::read_dir("/sys/class/drm") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut gpus: Vec<GpuInfo> = Vec::new();
+    for entry in cards.filter_map(|e| e.ok()) {
+        let card = entry.file_name().to_string_lossy().to_string();
+        // card0-HDMI-A-1 and friends are connectors of the same card
+        if !card.starts_with("card") || card.contains('-') {
+            continue;
+        }
+        let device = entry.path().join("device");
+        let read = |file: &str| {
+            fs::read_to_string(device.join(file))
+                .ok()
+                .map(|v| v.trim().to_string())
+        };
+        let vendor = match read("vendor").as_deref() {
+            Some("0x10de") => "NVIDIA",
+            Some("0x1002") => "AMD",
+            Some("0x8086") => "Intel",
+            _ => "Unknown",
+        };
+        let vram_bytes = read("mem_info_vram_total")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        let name = read("uevent")
+            .and_then(|uevent| {
+                uevent
+                    .lines()
+                    .find_map(|line| line.strip_prefix("PCI_SLOT_NAME=").map(String::from))
+            })
+            .and_then(|slot| lspci_device_name(&slot))
+            .unwrap_or_else(|| format!("{} graphics adapter", vendor));
+        if gpus.iter().any(|g| g.name == name) {
+            continue;
+        }
+        gpus.push(GpuInfo {
+            name,
+            vendor: vendor.to_string(),
+            vram_mb: vram_bytes / (1024 * 1024),
+        });
+    }
+    gpus
+}
+
+#[tauri::command]
+fn gpu_info() -> Vec<GpuInfo> {
+    detect_gpus()
+}
+
+#[tauri::command]
+fn gpu_compatibility() -> GpuCompatibility {
+    let gpus = detect_gpus();
+    // Judge the strongest adapter; laptops report the integrated GPU alongside the discrete one.
+    let best = gpus
+        .iter()
+        .max_by_key(|g| (!gpu_is_integrated(g), g.vram_mb))
+        .cloned();
+    let gpu = match best {
+        Some(gpu) => gpu,
+        None => {
+            return GpuCompatibility {
+                meets_minimum: true,
+                gpu: "Unknown".into(),
+                notes: vec!["Could not detect a graphics adapter.".into()],
+            }
+        }
+    };
+    let mut notes = Vec::new();
+    let integrated = gpu_is_integrated(&gpu);
+    if integrated {
+        notes.push(format!(
+            "{} is an integrated GPU; expect poor performance with the modpack's graphics settings.",
+            gpu.name
+        ));
+    }
+    if gpu.vram_mb > 0 && gpu.vram_mb < MIN_GPU_VRAM_MB {
+        notes.push(format!(
+            "{} has {} MB of video memory; the modpack recommends at least {} MB.",
+            gpu.name, gpu.vram_mb, MIN_GPU_VRAM_MB
+        ));
+    }
+    GpuCompatibility {
+        meets_minimum: !integrated && (gpu.vram_mb == 0 || gpu.vram_mb >= MIN_GPU_VRAM_MB),
+        gpu: gpu.name,
+        notes,
+    }
+}
+
+// One echo request bounded by NET_TIMEOUT; every ping spells the count and wait differently.
+#[cfg(windows)]
+fn ping_args(host: &str) -> Vec<String> {
+    let wait_ms = NET_TIMEOUT.as_millis().to_string();
+    vec!["-n".into(), "1".into(), "-w".into(), wait_ms, host.into()]
+}
+
+#[cfg(target_os = "macos")]
+fn ping_args(host: &str) -> Vec<String> {
+    let wait_secs = NET_TIMEOUT.as_secs().max(1).to_string();
+    vec!["-c".into(), "1".into(), "-t".into(), wait_secs, host.into()]
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn ping_args(host: &str) -> Vec<String> {
+    let wait_secs = NET_TIMEOUT.as_secs().max(1).to_string();
+    vec!["-c".into(), "1".into(), "-W".into(), wait_secs, host.into()]
+}
+
+fn ping_host(host: &str) -> Option<u64> {
+    let output = Command::new("ping").args(ping_args(host)).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let re = Regex::new(r"time[=<]\s*(\d+)(?:\.\d+)?\s*ms").ok()?;
+    let caps = re.captures(&stdout)?;
+    let value = caps.get(1)?.as_str().parse::<u64>().ok()?;
+    Some(value)
+}
+
+#[tauri::command]
+async fn get_server_status(host: String) -> Result<ServerStatus, LauncherError> {
+    tauri::async_runtime::spawn_blocking(move || run_get_server_status(host))
+        .await
+        .map_err(|e| LauncherError::Other(e.to_string()))?
+}
+
+fn run_get_server_status(host: String) -> Result<ServerStatus, LauncherError> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err("Host is empty".into());
+    }
+    let mut addrs = (host, 0)
+        .to_socket_addrs()
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?;
+    let ip = addrs
+        .next()
+        .ok_or_else(|| format!("No IP address found for {}", host))?
+        .ip()
+        .to_string();
+    let ping_ms = ping_host(host);
+    Ok(ServerStatus { ip, ping_ms })
+}
+
+fn port_spec_contains(spec: &str, port: u16) -> bool {
+    spec.split(',').map(str::trim).any(|part| {
+        if part.eq_ignore_ascii_case("any") {
+            return true;
+        }
+        match part.split_once('-') {
+            Some((lo, hi)) => match (lo.trim().parse::<u16>(), hi.trim().parse::<u16>()) {
+                (Ok(lo), Ok(hi)) => lo <= port && port <= hi,
+                _ => false,
+            },
+            None => part.parse::<u16>() == Ok(port),
+        }
+    })
+}
+
+// Finds an enabled outbound block rule covering UDP `port` in `netsh advfirewall` output.
+fn find_outbound_block_rule(port: u16) -> Option<String> {
+    let output = Command::new("netsh")
+        .args([
+            "advfirewall",
+            "firewall",
+            "show",
+            "rule",
+            "name=all",
+            "dir=out",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let field_re = Regex::new(r"(?m)^([A-Za-z ]+):\s*(.*?)\s*$").ok()?;
+    for block in stdout.split("\r\n\r\n").flat_map(|b| b.split("\n\n")) {
+        let mut name = String::new();
+        let (mut enabled, mut blocks, mut udp, mut port_match) = (false, false, false, false);
+        for cap in field_re.captures_iter(block) {
+            let value = cap[2].trim();
+            match cap[1].trim().to_ascii_lowercase().as_str() {
+                "rule name" => name = value.to_string(),
+                "enabled" => enabled = value.eq_ignore_ascii_case("yes"),
+                "action" => blocks = value.eq_ignore_ascii_case("block"),
+                "protocol" => {
+                    udp = value.eq_ignore_ascii_case("udp") || value.eq_ignore_ascii_case("any")
+                }
+                "remoteport" => port_match = port_spec_contains(value, port),
+                _ => {}
+            }
+        }
+        if enabled && blocks && udp && port_match {
+            return Some(name);
+        }
+    }
+    None
+}
+
+#[tauri::command]
+async fn preflight_connectivity() -> PreflightReport {
+    tauri::async_runtime::spawn_blocking(run_preflight_connectivity)
+        .await
+        .unwrap_or(PreflightReport {
+            dns_ok: false,
+            tcp_ok: false,
+            udp_ok: false,
+        })
+}
+
+fn run_preflight_connectivity() -> PreflightReport {
+    let mut report = PreflightReport {
+        dns_ok: false,
+        tcp_ok: false,
+        udp_ok: false,
+    };
+    let addr = match (SERVER_IP, SERVER_PORT)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+    {
+        Some(addr) => addr,
+        None => return report,
+    };
+    report.dns_ok = true;
+    report.tcp_ok = TcpStream::connect_timeout(&addr, NET_TIMEOUT).is_ok();
+    report.udp_ok = query_server_info(SERVER_IP, SERVER_PORT, NET_TIMEOUT).is_ok();
+    log::info!(
+        "preflight_connectivity: dns_ok={} tcp_ok={} udp_ok={}",
+        report.dns_ok,
+        report.tcp_ok,
+        report.udp_ok
+    );
+    report
+}
+
+#[tauri::command]
+async fn check_outbound_port(port: u16) -> OutboundPortCheck {
+    tauri::async_runtime::spawn_blocking(move || run_check_outbound_port(port))
+        .await
+        .unwrap_or_else(|e| OutboundPortCheck {
+            port,
+            allowed: false,
+            reason: format!("Could not check outbound UDP {}: {}", port, e),
+            blocking_rule: None,
+            suggested_rule: None,
+        })
+}
+
+fn run_check_outbound_port(port: u16) -> OutboundPortCheck {
+    let suggested_rule = format!(
+        "netsh advfirewall firewall add rule name=\"13th Pandemic UDP {port}\" dir=out action=allow protocol=UDP remoteport={port}"
+    );
+    if let Some(rule) = find_outbound_block_rule(port) {
+        return OutboundPortCheck {
+            port,
+            allowed: false,
+            reason: format!(
+                "Windows Firewall is blocking outbound UDP {} (rule \"{}\")",
+                port, rule
+            ),
+            blocking_rule: Some(rule),
+            suggested_rule: Some(suggested_rule),
+        };
+    }
+    // Without a matching rule, fall back to whether the OS lets a UDP datagram out at all.
+    let probe = UdpSocket::bind("0.0.0.0:0")
+        .and_then(|sock| sock.set_write_timeout(Some(NET_TIMEOUT)).map(|_| sock))
+        .and_then(|sock| sock.connect((SERVER_IP, port)).map(|_| sock))
+        .and_then(|sock| sock.send(&[0xff, 0xff, 0xff, 0xff]));
+    match probe {
+        Ok(_) => OutboundPortCheck {
+            port,
+            allowed: true,
+            reason: format!("Outbound UDP {} is not blocked locally", port),
+            blocking_rule: None,
+            suggested_rule: None,
+        },
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => OutboundPortCheck {
+            port,
+            allowed: false,
+            reason: format!("The OS firewall refused outbound UDP {}", port),
+            blocking_rule: None,
+            suggested_rule: Some(suggested_rule),
+        },
+        Err(err) => OutboundPortCheck {
+            port,
+            allowed: true,
+            reason: format!(
+                "Could not probe UDP {} ({}); no blocking rule found",
+                port, err
+            ),
+            blocking_rule: None,
+            suggested_rule: None,
+        },
+    }
+}
+
+fn launcher_root(real_workshop_path: &Path) -> PathBuf {
+    real_workshop_path
+        .join("mods")
+        .join("13thPandemic")
+        .join("Launcher")
+}
+
+// Saved settings or a moved Steam library can leave a path that exists but isn't the modpack.
+fn validate_workshop_path(p: &Path) -> Result<(), LauncherError> {
+    if !p.exists() {
+        return Err(LauncherError::InvalidWorkshopPath(format!(
+            "{} does not exist",
+            p.display()
+        )));
+    }
+    if !p.is_dir() {
+        return Err(LauncherError::InvalidWorkshopPath(format!(
+            "{} is not a folder",
+            p.display()
+        )));
+    }
+    for dir in [optimizations_source_root(p), workshop_zomboid_root(p)] {
+        if !dir.is_dir() {
+            return Err(LauncherError::InvalidWorkshopPath(format!(
+                "{} is missing",
+                dir.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn check_workshop_path(workshop_path: String) -> Result<(), LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    validate_workshop_path(Path::new(&workshop_path))
+}
+
+// First semver-ish token, e.g. "1.4" or "v2.0.3-beta" -> "2.0.3-beta".
+fn parse_version(text: &str) -> Option<String> {
+    let re = Regex::new(r"\d+(?:\.\d+){1,2}(?:-[0-9A-Za-z.]+)?").ok()?;
+    re.find(text).map(|m| m.as_str().to_string())
+}
+
+fn read_modpack_version(workshop_path: &Path) -> Option<String> {
+    let modpack_root = workshop_path.join("mods").join("13thPandemic");
+    if let Ok(txt) = fs::read_to_string(modpack_root.join("version.txt")) {
+        if let Some(version) = parse_version(&txt) {
+            return Some(version);
+        }
+    }
+    let raw = fs::read_to_string(modpack_root.join("modpack.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    json.get("version")
+        .and_then(|v| v.as_str())
+        .and_then(parse_version)
+}
+
+// Written into the cachedir on each launch so the next one can tell which pack made the saves.
+const CACHEDIR_VERSION_MARKER: &str = ".13p_modpack_version";
+
+// Saves made under another modpack version can crash PZ or get corrupted on load.
+fn cachedir_version_mismatch(cachedir: &Path, current: &str) -> Option<String> {
+    let has_saves = fs::read_dir(cachedir.join("Saves"))
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if !has_saves {
+        return None;
+    }
+    let previous = fs::read_to_string(cachedir.join(CACHEDIR_VERSION_MARKER)).ok()?;
+    let previous = previous.trim();
+    (!previous.is_empty() && previous != current).then(|| previous.to_string())
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .split('-')
+        .next()
+        .unwrap_or("")
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+async fn fetch_remote_modpack_version() -> Option<String> {
+    let body = fetch_json(MODPACK_VERSION_URL).await?;
+    body["version"].as_str().and_then(parse_version)
+}
+
+#[tauri::command]
+async fn check_modpack_update(workshop_path: String) -> UpdateStatus {
+    let local = read_modpack_version(Path::new(&workshop_path));
+    let remote = fetch_remote_modpack_version().await;
+    if remote.is_none() {
+        log::error!(
+            "check_modpack_update: could not fetch {}",
+            MODPACK_VERSION_URL
+        );
+    }
+    let update_available = match (&local, &remote) {
+        (Some(local), Some(remote)) => version_parts(remote) > version_parts(local),
+        (None, Some(_)) => true,
+        _ => false,
+    };
+    UpdateStatus {
+        local: local.unwrap_or_else(|| "unknown".into()),
+        remote,
+        update_available,
+    }
+}
+
+async fn fetch_json(url: &str) -> Option<serde_json::Value> {
+    let client = reqwest::Client::builder()
+        .timeout(NET_TIMEOUT)
+        .build()
+        .ok()?;
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .ok()?
+        .json()
+        .await
+        .ok()
+}
+
+#[tauri::command]
+async fn check_launcher_update() -> LauncherUpdate {
+    let current = env!("CARGO_PKG_VERSION").to_string();
+    let body = fetch_json(LAUNCHER_UPDATE_URL).await;
+    if body.is_none() {
+        log::error!(
+            "check_launcher_update: could not fetch {}",
+            LAUNCHER_UPDATE_URL
+        );
+    }
+    let latest = body
+        .as_ref()
+        .and_then(|b| b["version"].as_str())
+        .and_then(parse_version);
+    let download_url = body
+        .as_ref()
+        .and_then(|b| b["download_url"].as_str())
+        .map(str::to_string);
+    let update_available = latest
+        .as_deref()
+        .is_some_and(|latest| version_parts(latest) > version_parts(&current));
+    LauncherUpdate {
+        current,
+        latest,
+        update_available,
+        download_url,
+    }
+}
+
+#[tauri::command]
+fn modpack_version(workshop_path: String) -> String {
+    read_modpack_version(Path::new(&workshop_path)).unwrap_or_else(|| "unknown".into())
+}
+
+const CHANGELOG_FILES: &[&str] = &["CHANGELOG.md", "changelog.txt"];
+const CHANGELOG_MAX_BYTES: u64 = 256 * 1024;
+
+fn changelog_path(workshop_path: &str) -> Result<PathBuf, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let modpack_root = Path::new(workshop_path).join("mods").join("13thPandemic");
+    CHANGELOG_FILES
+        .iter()
+        .map(|name| modpack_root.join(name))
+        .find(|p| p.is_file())
+        .ok_or_else(|| "The modpack does not include a changelog".into())
+}
+
+#[tauri::command]
+fn read_changelog(workshop_path: String) -> Result<String, LauncherError> {
+    let path = changelog_path(&workshop_path)?;
+    let mut bytes = Vec::new();
+    fs::File::open(&path)?
+        .take(CHANGELOG_MAX_BYTES)
+        .read_to_end(&mut bytes)?;
+    let mut text = String::from_utf8_lossy(&bytes).to_string();
+    if fs::metadata(&path)?.len() > CHANGELOG_MAX_BYTES {
+        text.push_str("\n\n(Changelog truncated; open it for the full history.)");
+    }
+    Ok(text)
+}
+
+#[tauri::command]
+fn open_changelog(workshop_path: String) -> Result<String, LauncherError> {
+    let path = changelog_path(&workshop_path)?;
+    open::that(&path)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+// A partially synced workshop download keeps its folders but can leave truncated files behind.
+#[tauri::command]
+fn verify_modpack(workshop_path: String) -> Result<VerifyReport, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let modpack_root = Path::new(&workshop_path).join("mods").join("13thPandemic");
+    let raw = fs::read_to_string(modpack_root.join("checksums.json"))
+        .map_err(|e| format!("Failed to read checksums.json: {}", e))?;
+    let checksums: std::collections::BTreeMap<String, String> = serde_json::from_str(&raw)?;
+    let mut missing = Vec::new();
+    let mut mismatched = Vec::new();
+    for (rel, expected) in &checksums {
+        let rel_path = safe_relative_path(rel)
+            .ok_or_else(|| format!("checksums.json lists an unsafe path: {}", rel))?;
+        match file_sha256(&modpack_root.join(rel_path)) {
+            Ok(hash) if hash.eq_ignore_ascii_case(expected) => {}
+            Ok(_) => mismatched.push(rel.clone()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => missing.push(rel.clone()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    log::info!(
+        "verify_modpack: {} missing, {} mismatched",
+        missing.len(),
+        mismatched.len()
+    );
+    Ok(VerifyReport {
+        ok: missing.is_empty() && mismatched.is_empty(),
+        missing,
+        mismatched,
+    })
+}
+
+fn optimizations_source_root(real_workshop_path: &Path) -> PathBuf {
+    real_workshop_path
+        .join("mods")
+        .join("13thPandemic")
+        .join("ProjectZomboid")
+}
+
+fn install_backup_root(pz_install: &Path) -> PathBuf {
+    pz_install.join(".13p_backup")
+}
+
+fn optimization_manifest_path(real_workshop_path: &Path) -> PathBuf {
+    launcher_root(real_workshop_path).join("optimizations.json")
+}
+
+fn apply_history_path(real_workshop_path: &Path) -> PathBuf {
+    launcher_root(real_workshop_path).join("apply_history.json")
+}
+
+fn config_snapshot_root(real_workshop_path: &Path) -> PathBuf {
+    launcher_root(real_workshop_path).join("config_snapshot")
+}
+
+fn launch_times_path(real_workshop_path: &Path) -> PathBuf {
+    launcher_root(real_workshop_path).join("launch_times.json")
+}
+
+fn launcher_log_path(real_workshop_path: &Path) -> PathBuf {
+    launcher_root(real_workshop_path).join("debug.txt")
+}
+
+// Steam writes the manifest as soon as a download starts; StateFlags is 4 only once the game is
+// fully installed with no pending update.
+fn pz_install_complete(libs: &[PathBuf]) -> bool {
+    libs.iter()
+        .map(|lib| lib.join("appmanifest_108600.acf"))
+        .filter(|manifest| manifest.exists())
+        .filter_map(|manifest| read_app_manifest(&manifest).ok())
+        .any(|info| info.state_flags == Some(4))
+}
+
+// Leftover installs on other drives are listed too; the first library wins by default.
+fn pz_install_dirs(libs: &[PathBuf]) -> Vec<PathBuf> {
+    libs.iter()
+        .map(|lib| lib.join("common").join("ProjectZomboid"))
+        .filter(|p| p.exists())
+        .collect()
+}
+
+fn pz_install_dir(libs: &[PathBuf]) -> Option<PathBuf> {
+    pz_install_dirs(libs).into_iter().next()
+}
+
+#[tauri::command]
+fn list_pz_installs() -> Vec<String> {
+    pz_install_dirs(&parse_libraryfolders(&steam_root()))
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+// An explicit choice is remembered in settings; otherwise the last choice is reused while it
+// still looks like a PZ install.
+fn remembered_game_root(
+    app_handle: &tauri::AppHandle,
+    game_root: Option<String>,
+) -> Option<String> {
+    match game_root.filter(|r| !r.trim().is_empty()) {
+        Some(root) => {
+            if is_pz_install(Path::new(&root)) {
+                let _ = settings::update_settings(app_handle, |settings| {
+                    settings.game_root = Some(root.clone())
+                });
+            }
+            Some(root)
+        }
+        None => settings::load_settings(app_handle.clone())
+            .game_root
+            .filter(|root| is_pz_install(Path::new(root))),
+    }
+}
+
+// An empty override clears the remembered one and goes back to the workshop folder.
+fn remembered_cachedir(
+    app_handle: &tauri::AppHandle,
+    cachedir_override: Option<String>,
+    steam_root: &str,
+    libs: &[PathBuf],
+) -> Result<Option<PathBuf>, LauncherError> {
+    match cachedir_override.map(|dir| dir.trim().to_string()) {
+        Some(dir) => {
+            let dir = Some(dir).filter(|d| !d.is_empty());
+            // Only a path that passed validation is remembered for the next launch.
+            let validated = dir
+                .as_deref()
+                .map(|d| validate_cachedir_override(d, steam_root, libs))
+                .transpose()?;
+            let _ =
+                settings::update_settings(app_handle, |settings| settings.cachedir_override = dir);
+            Ok(validated)
+        }
+        None => settings::load_settings(app_handle.clone())
+            .cachedir_override
+            .map(|d| validate_cachedir_override(&d, steam_root, libs))
+            .transpose(),
+    }
+}
+
+// PZ writes saves and configs into the cachedir, so it must stay clear of Steam and game files.
+fn validate_cachedir_override(
+    dir: &str,
+    steam_root: &str,
+    libs: &[PathBuf],
+) -> Result<PathBuf, LauncherError> {
+    let path = PathBuf::from(dir);
+    if !path.is_absolute() {
+        return Err(format!("The cachedir \"{}\" must be an absolute path", dir).into());
+    }
+    let resolved = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    let protected =
+        std::iter::once(PathBuf::from(steam_root)).chain(libs.iter().map(|lib| lib.join("common")));
+    for root in protected {
+        let root = fs::canonicalize(&root).unwrap_or(root);
+        if resolved.starts_with(&root) {
+            return Err(format!(
+                "The cachedir {} is inside {}; pick a folder outside the Steam install",
+                path.display(),
+                root.display()
+            )
+            .into());
+        }
+    }
+    Ok(path)
+}
+
+// Probes in Steam's scratch folder on the same library so nothing is written into the workshop
+// item itself; paths outside a library get a throwaway folder of their own.
+fn fs_case_sensitive(dir: &Path) -> io::Result<bool> {
+    let base = dir
+        .ancestors()
+        .find(|a| {
+            a.file_name()
+                .is_some_and(|n| n.eq_ignore_ascii_case("steamapps"))
+        })
+        .map(|steamapps| steamapps.join("temp"))
+        .unwrap_or_else(|| dir.to_path_buf());
+    let probe_dir = base.join(format!(".13p-case-probe-{}", std::process::id()));
+    fs::create_dir_all(&probe_dir)?;
+    let probed = fs::write(probe_dir.join("Test"), b"").map(|_| !probe_dir.join("test").exists());
+    let _ = fs::remove_dir_all(&probe_dir);
+    probed
+}
+
+// Resolves `rel` under `root` ignoring ASCII case, one path component at a time.
+fn find_case_insensitive(root: &Path, rel: &Path) -> Option<PathBuf> {
+    let mut current = root.to_path_buf();
+    for component in rel.components() {
+        let name = component.as_os_str().to_string_lossy();
+        let exact = current.join(component);
+        if exact.exists() {
+            current = exact;
+            continue;
+        }
+        current = fs::read_dir(&current)
+            .ok()?
+            .flatten()
+            .find(|e| e.file_name().to_string_lossy().eq_ignore_ascii_case(&name))?
+            .path();
+    }
+    Some(current)
+}
+
+// Where `rel` lands under `root`, reusing whatever casing already exists there so a
+// case-sensitive install doesn't end up with two copies of a folder or file.
+fn resolve_dest_path(root: &Path, rel: &Path) -> PathBuf {
+    if let Some(existing) = find_case_insensitive(root, rel) {
+        return existing;
+    }
+    match (rel.parent(), rel.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            resolve_dest_path(root, parent).join(name)
+        }
+        _ => root.join(rel),
+    }
+}
+
+fn list_files_recursive(root: &Path) -> io::Result<Vec<PathBuf>> {
+    list_files_walk(root, true)
+}
+
+// Mods may be a junction that points back into the tree, so each real directory is walked once.
+fn list_files_walk(root: &Path, follow_links: bool) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if !visited.insert(fs::canonicalize(&dir).unwrap_or_else(|_| dir.clone())) {
+            continue;
+        }
+        for ent in fs::read_dir(&dir)? {
+            let ent = ent?;
+            let p = ent.path();
+            if p.is_dir() {
+                if follow_links || !ent.file_type()?.is_symlink() {
+                    stack.push(p);
+                }
+            } else {
+                files.push(p);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn file_sha256(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    let digest = hasher.finalize();
+    let mut hex = String::with_capacity(64);
+    for byte in digest {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    Ok(hex)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    let mut hex = String::with_capacity(64);
+    for byte in digest {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+fn build_manifest(root: &Path) -> io::Result<Vec<ManifestEntry>> {
+    let mut files = list_files_recursive(root)?;
+    files.sort();
+    let mut entries = Vec::with_capacity(files.len());
+    for path in files {
+        let rel = path
+            .strip_prefix(root)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Invalid manifest path"))?;
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        let size = fs::metadata(&path)?.len();
+        let hash = file_sha256(&path)?;
+        entries.push(ManifestEntry {
+            path: rel_str,
+            size,
+            hash,
+        });
+    }
+    Ok(entries)
+}
+
+// Hash over the sorted `path:hash` lines so two clients with identical files agree.
+fn tree_hash(entries: &[ManifestEntry]) -> String {
+    let mut lines: Vec<String> = entries
+        .iter()
+        .map(|e| format!("{}:{}\n", e.path, e.hash))
+        .collect();
+    lines.sort();
+    sha256_hex(lines.concat().as_bytes())
+}
+
+// Stored in settings after a successful apply; unlike tree_hash it also covers sizes.
+fn aggregate_hash(entries: &[ManifestEntry]) -> String {
+    let mut lines: Vec<String> = entries
+        .iter()
+        .map(|e| format!("{}:{}:{}\n", e.path, e.size, e.hash))
+        .collect();
+    lines.sort();
+    sha256_hex(lines.concat().as_bytes())
+}
+
+fn write_manifest(path: &Path, entries: &[ManifestEntry]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let manifest = OptimizationManifest {
+        entries: entries.to_vec(),
+    };
+    let json =
+        serde_json::to_string_pretty(&manifest).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+fn read_manifest(path: &Path) -> io::Result<OptimizationManifest> {
+    let raw = fs::read_to_string(path)?;
+    serde_json::from_str(&raw).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+}
+
+fn manifest_matches_dest(
+    entries: &[ManifestEntry],
+    dst_root: &Path,
+    stats: &mut ApplyStats,
+) -> io::Result<bool> {
+    if entries.is_empty() {
+        return Ok(false);
+    }
+    for entry in entries {
+        if !dest_entry_matches(entry, dst_root, stats)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn dest_entry_matches(
+    entry: &ManifestEntry,
+    dst_root: &Path,
+    stats: &mut ApplyStats,
+) -> io::Result<bool> {
+    let mut dest_path = dst_root.join(Path::new(&entry.path));
+    let meta = match fs::metadata(&dest_path) {
+        Ok(m) => m,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            match find_case_insensitive(dst_root, Path::new(&entry.path)) {
+                Some(found) => {
+                    let meta = fs::metadata(&found)?;
+                    dest_path = found;
+                    meta
+                }
+                None => return Ok(false),
+            }
+        }
+        Err(err) => return Err(err),
+    };
+    if meta.len() != entry.size {
+        return Ok(false);
+    }
+    let hash = file_sha256(&dest_path)?;
+    stats.hashed += 1;
+    Ok(hash == entry.hash)
+}
+
+// Sizes alone miss same-length edits, so any source file touched since the manifest was
+// written also invalidates it.
+fn manifest_matches_src(
+    entries: &[ManifestEntry],
+    src_root: &Path,
+    written_at: SystemTime,
+) -> io::Result<bool> {
+    if entries.is_empty() {
+        return Ok(false);
+    }
+    let src_files = list_files_recursive(src_root)?;
+    if src_files.len() != entries.len() {
+        return Ok(false);
+    }
+    for entry in entries {
+        let src_path = src_root.join(Path::new(&entry.path));
+        let meta = match fs::metadata(&src_path) {
+            Ok(m) => m,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        if meta.len() != entry.size || meta.modified()? >= written_at {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+const SPOT_CHECK_FILES: usize = 3;
+
+// Every destination file must still have the right size, but only a few evenly spaced ones are
+// re-hashed.
+fn spot_check_dest(
+    entries: &[ManifestEntry],
+    dst_root: &Path,
+    stats: &mut ApplyStats,
+) -> io::Result<bool> {
+    if entries.is_empty() {
+        return Ok(false);
+    }
+    for entry in entries {
+        match fs::metadata(dst_root.join(Path::new(&entry.path))) {
+            Ok(meta) if meta.len() == entry.size => {}
+            Ok(_) => return Ok(false),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err),
+        }
+    }
+    let step = (entries.len() / SPOT_CHECK_FILES).max(1);
+    let mut hashed: u64 = 0;
+    for entry in entries.iter().step_by(step).take(SPOT_CHECK_FILES) {
+        hashed += 1;
+        if file_sha256(&dst_root.join(Path::new(&entry.path)))? != entry.hash {
+            stats.hashed += hashed;
+            return Ok(false);
+        }
+    }
+    // Each file counts once: either re-hashed above or trusted on its size alone.
+    stats.hashed += hashed;
+    stats.skipped_fast += entries.len() as u64 - hashed;
+    Ok(true)
+}
+
+fn optimizations_applied(
+    src_root: &Path,
+    dst_root: &Path,
+    manifest_path: &Path,
+    applied_hash: Option<&str>,
+    stats: &mut ApplyStats,
+) -> io::Result<bool> {
+    if !dst_root.exists() {
+        return Ok(false);
+    }
+    if manifest_path.exists() {
+        let manifest = read_manifest(manifest_path)?;
+        let written_at = fs::metadata(manifest_path)?.modified()?;
+        if manifest_matches_src(&manifest.entries, src_root, written_at)? {
+            // No source file changed size or was touched since the manifest was written, so its
+            // hashes stand in for the source without re-reading it. When they are also what the
+            // last apply recorded, a spot check of the install is enough.
+            if applied_hash == Some(aggregate_hash(&manifest.entries).as_str()) {
+                return spot_check_dest(&manifest.entries, dst_root, stats);
+            }
+            return manifest_matches_dest(&manifest.entries, dst_root, stats);
+        }
+        let entries = build_manifest(src_root)?;
+        let matches = manifest_matches_dest(&entries, dst_root, stats)?;
+        if matches {
+            write_manifest(manifest_path, &entries)?;
+        }
+        return Ok(matches);
+    }
+    let entries = build_manifest(src_root)?;
+    let matches = manifest_matches_dest(&entries, dst_root, stats)?;
+    if matches {
+        write_manifest(manifest_path, &entries)?;
+    }
+    Ok(matches)
+}
+
+// Keeps the apply result small enough for IPC on very large modpacks
+const CHANGED_FILES_LIMIT: usize = 500;
+const COPY_VERIFY_RETRIES: u32 = 2;
+
+#[derive(Default)]
+struct CopyReport {
+    copied: u64,
+    replaced: u64,
+    // Destination already identical to the source
+    skipped: u64,
+    backed_up: u64,
+    rewritten: Vec<String>,
+    replaced_files: Vec<String>,
+    created_files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct BackupManifest {
+    created_at: u64,
+    // Originals saved next to this manifest before being overwritten
+    replaced: Vec<String>,
+    // Files that did not exist before the apply
+    created: Vec<String>,
+}
+
+// Only called once something was actually replaced or created, so no-op applies leave no
+// empty timestamp folders behind.
+fn write_backup_manifest(
+    backup_root: &Path,
+    replaced: Vec<String>,
+    created: Vec<String>,
+) -> io::Result<()> {
+    fs::create_dir_all(backup_root)?;
+    let manifest = BackupManifest {
+        created_at: unix_now(),
+        replaced,
+        created,
+    };
+    fs::write(
+        backup_root.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )
+}
+
+const COPY_BUFFER_BYTES: usize = 64 * 1024;
+// Large map files would otherwise look frozen until the whole file is written.
+const PROGRESS_EMIT_BYTES: u64 = 4 * 1024 * 1024;
+
+// Reports bytes written through `on_bytes` every PROGRESS_EMIT_BYTES and once more at the end.
+fn copy_with_progress(src: &Path, dst: &Path, on_bytes: &mut dyn FnMut(u64)) -> io::Result<()> {
+    let mut reader = io::BufReader::new(fs::File::open(src)?);
+    let mut writer = io::BufWriter::new(fs::File::create(dst)?);
+    let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+    let mut pending = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read])?;
+        pending += read as u64;
+        if pending >= PROGRESS_EMIT_BYTES {
+            on_bytes(pending);
+            pending = 0;
+        }
+    }
+    writer.flush()?;
+    if pending > 0 {
+        on_bytes(pending);
+    }
+    fs::set_permissions(dst, fs::metadata(src)?.permissions())
+}
+
+// fs::copy leaves the destination mtime at "now"; external sync tools key off timestamps.
+fn copy_preserving_mtime(src: &Path, dst: &Path, on_bytes: &mut dyn FnMut(u64)) -> io::Result<()> {
+    copy_with_progress(src, dst, on_bytes)?;
+    let mtime = filetime::FileTime::from_last_modification_time(&fs::metadata(src)?);
+    filetime::set_file_mtime(dst, mtime)
+}
+
+const TEMP_COPY_MARKER: &str = ".tmp-";
+
+fn temp_sibling(dst: &Path) -> PathBuf {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+        ^ u128::from(std::process::id());
+    let mut name = dst.file_name().unwrap_or_default().to_os_string();
+    name.push(format!("{}{:x}", TEMP_COPY_MARKER, nonce));
+    dst.with_file_name(name)
+}
+
+// Writes next to `dst` and renames over it, so a crash mid-copy never leaves a truncated file
+// in the install; the rename is atomic on the same volume.
+fn copy_atomic(src: &Path, dst: &Path, on_bytes: &mut dyn FnMut(u64)) -> io::Result<()> {
+    let tmp = temp_sibling(dst);
+    let result = copy_preserving_mtime(src, &tmp, on_bytes).and_then(|_| fs::rename(&tmp, dst));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+// Removes leftovers of copy_atomic from a run that died between the write and the rename.
+fn remove_stray_temp_copies(dst_root: &Path, rels: &[&Path]) {
+    let mut dirs: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+    for rel in rels {
+        let dest = resolve_dest_path(dst_root, rel);
+        if let (Some(dir), Some(name)) = (dest.parent(), dest.file_name()) {
+            dirs.entry(dir.to_path_buf())
+                .or_default()
+                .insert(name.to_string_lossy().to_string());
+        }
+    }
+    for (dir, bases) in dirs {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Only leftovers of the files about to be copied; anything else isn't ours to touch.
+            let stray = name
+                .rsplit_once(TEMP_COPY_MARKER)
+                .is_some_and(|(base, nonce)| {
+                    bases.contains(base)
+                        && !nonce.is_empty()
+                        && nonce.bytes().all(|b| b.is_ascii_hexdigit())
+                });
+            if stray && entry.path().is_file() {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+// Copies `src` over `dst`; with `verify`, re-reads the destination and rewrites it until it
+// matches the source, returning how many rewrites were needed. Only the first write is reported
+// through `on_bytes`.
+fn copy_file_checked(
+    src: &Path,
+    dst: &Path,
+    verify: bool,
+    on_bytes: &mut dyn FnMut(u64),
+) -> io::Result<u32> {
+    copy_atomic(src, dst, on_bytes)?;
+    if !verify {
+        return Ok(0);
+    }
+    let expected_len = fs::metadata(src)?.len();
+    let expected_hash = file_sha256(src)?;
+    let mut rewrites = 0;
+    loop {
+        if fs::metadata(dst)?.len() == expected_len && file_sha256(dst)? == expected_hash {
+            return Ok(rewrites);
+        }
+        if rewrites == COPY_VERIFY_RETRIES {
+            return Err(io::Error::other(format!(
+                "{} still differs from its source after {} rewrites",
+                dst.display(),
+                rewrites
+            )));
+        }
+        copy_atomic(src, dst, &mut |_| {})?;
+        rewrites += 1;
+    }
+}
+
+// Length first so most changed files are ruled out without hashing.
+fn same_contents(a: &Path, b: &Path) -> io::Result<bool> {
+    let (meta_a, meta_b) = (fs::metadata(a)?, fs::metadata(b)?);
+    if !meta_b.is_file() || meta_a.len() != meta_b.len() {
+        return Ok(false);
+    }
+    Ok(file_sha256(a)? == file_sha256(b)?)
+}
+
+#[derive(Default)]
+struct CopyPlan {
+    would_create: Vec<String>,
+    would_replace: Vec<String>,
+    identical: Vec<String>,
+}
+
+fn plan_copy(src_root: &Path, dst_root: &Path) -> io::Result<CopyPlan> {
+    let mut plan = CopyPlan::default();
+    let mut files = list_files_recursive(src_root)?;
+    files.sort();
+    for s in files {
+        let rel = s.strip_prefix(src_root).unwrap();
+        let d = dst_root.join(rel);
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        if !d.exists() {
+            plan.would_create.push(rel_str);
+        } else if same_contents(&s, &d)? {
+            plan.identical.push(rel_str);
+        } else {
+            plan.would_replace.push(rel_str);
+        }
+    }
+    Ok(plan)
+}
+
+fn copy_dir_replace(
+    src_root: &Path,
+    dst_root: &Path,
+    backup_root: Option<&Path>,
+    verify: bool,
+    on_progress: &mut dyn FnMut(usize, usize, &Path, u64),
+) -> io::Result<CopyReport> {
+    let mut report = CopyReport::default();
+    let mut files = list_files_recursive(src_root)?;
+    files.sort();
+    let total = files.len();
+    let rels: Vec<&Path> = files
+        .iter()
+        .filter_map(|f| f.strip_prefix(src_root).ok())
+        .collect();
+    remove_stray_temp_copies(dst_root, &rels);
+    // Originals are staged before being overwritten so a failure part-way leaves `dst_root` as
+    // it was. Staging inside the install keeps it on the same volume as the files it protects.
+    let staging = install_backup_root(dst_root).join(format!(
+        ".staging-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    let mut created: Vec<PathBuf> = Vec::new();
+    for (idx, s) in files.into_iter().enumerate() {
+        let rel = s.strip_prefix(src_root).unwrap();
+        let d = resolve_dest_path(dst_root, rel);
+        let existed = d.exists();
+        if existed && same_contents(&s, &d).unwrap_or(false) {
+            report.skipped += 1;
+            let len = fs::metadata(&s).map(|m| m.len()).unwrap_or(0);
+            on_progress(idx + 1, total, rel, len);
+            continue;
+        }
+        if !existed {
+            created.push(d.strip_prefix(dst_root).unwrap_or(rel).to_path_buf());
+        }
+        let step = (|| -> io::Result<(u32, bool)> {
+            if let Some(parent) = d.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut backed_up = false;
+            if existed {
+                let staged = staging.join(rel);
+                if let Some(parent) = staged.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&d, &staged)?;
+                if let Some(backup_root) = backup_root {
+                    let backup_path = backup_root.join(rel);
+                    if !backup_path.exists() {
+                        if let Some(parent) = backup_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::copy(&d, &backup_path)?;
+                        backed_up = true;
+                    }
+                }
+            }
+            let rewrites = copy_file_checked(&s, &d, verify, &mut |bytes| {
+                on_progress(idx, total, rel, bytes)
+            })?;
+            Ok((rewrites, backed_up))
+        })();
+        let (rewrites, backed_up) = match step {
+            Ok(done) => done,
+            Err(err) => {
+                let restored = rollback_copy(dst_root, &staging, &created);
+                let _ = fs::remove_dir_all(&staging);
+                let _ = fs::remove_dir(install_backup_root(dst_root));
+                let mut message = format!("Failed to copy {}: {}", d.display(), err);
+                if let Err(rollback_err) = restored {
+                    message.push_str(&format!(" (rollback also failed: {})", rollback_err));
+                }
+                return Err(io::Error::new(err.kind(), message));
+            }
+        };
+        if backed_up {
+            report.backed_up += 1;
+        }
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        if existed {
+            report.replaced += 1;
+            report.replaced_files.push(rel_str);
+        } else {
+            report.copied += 1;
+            report.created_files.push(rel_str);
+        }
+        if rewrites > 0 {
+            report
+                .rewritten
+                .push(rel.to_string_lossy().replace('\\', "/"));
+        }
+        on_progress(idx + 1, total, rel, 0);
+    }
+    let _ = fs::remove_dir_all(&staging);
+    // Only succeeds when no backups were ever taken, so a first apply leaves no empty folder.
+    let _ = fs::remove_dir(install_backup_root(dst_root));
+    Ok(report)
+}
+
+// Timestamped backup folders, newest first.
+fn install_backups(pz_install: &Path) -> Vec<(u64, PathBuf)> {
+    let mut backups: Vec<(u64, PathBuf)> = fs::read_dir(install_backup_root(pz_install))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let ts = e.file_name().to_string_lossy().parse::<u64>().ok()?;
+                    Some((ts, e.path()))
+                })
+                .filter(|(_, path)| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.0));
+    backups
+}
+
+fn latest_install_backup(pz_install: &Path) -> Option<PathBuf> {
+    install_backups(pz_install)
+        .into_iter()
+        .map(|(_, path)| path)
+        .find(|path| path.join("manifest.json").is_file())
+}
+
+// The install every apply/check/backup command works on: an explicit game root (remembered for
+// next time), else the remembered one, else the detected one.
+fn remembered_install_dir(
+    app_handle: &tauri::AppHandle,
+    game_root: Option<String>,
+) -> Result<PathBuf, LauncherError> {
+    let game_root = remembered_game_root(app_handle, game_root);
+    resolve_apply_dest(&parse_libraryfolders(&steam_root()), game_root.as_deref())
+}
+
+#[tauri::command]
+fn backup_usage(app_handle: tauri::AppHandle) -> BackupUsage {
+    let backups: Vec<BackupEntry> = remembered_install_dir(&app_handle, None)
+        .map(|dest| install_backups(&dest))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(timestamp, path)| BackupEntry {
+            timestamp,
+            bytes: dir_size(&path).unwrap_or(0),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect();
+    BackupUsage {
+        total_bytes: backups.iter().map(|b| b.bytes).sum(),
+        backups,
+    }
+}
+
+// Deletes all but the `keep` newest backups and returns the bytes freed.
+#[tauri::command]
+fn prune_backups(app_handle: tauri::AppHandle, keep: usize) -> Result<u64, LauncherError> {
+    let dest = remembered_install_dir(&app_handle, None)?;
+    let mut freed = 0;
+    for (_, path) in install_backups(&dest).into_iter().skip(keep) {
+        let bytes = dir_size(&path).unwrap_or(0);
+        fs::remove_dir_all(&path)
+            .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        freed += bytes;
+    }
+    log::info!("prune_backups: keep={} freed={} bytes", keep, freed);
+    Ok(freed)
+}
+
+// Support escape hatch: forgets every remembered choice and optionally drops install backups.
+#[tauri::command]
+fn factory_reset(app_handle: tauri::AppHandle, remove_backups: bool) -> Result<(), LauncherError> {
+    // Resolved before the settings go away since they may hold the chosen install.
+    let game_root = settings::load_settings(app_handle.clone()).game_root;
+    let dest = if remove_backups {
+        Some(resolve_apply_dest(
+            &parse_libraryfolders(&steam_root()),
+            game_root.as_deref(),
+        )?)
+    } else {
+        None
+    };
+    match fs::remove_file(settings::settings_path(&app_handle)?) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+    }
+    if let Some(cache) = collection_cache_path(&app_handle) {
+        let _ = fs::remove_file(cache);
+    }
+    if let Some(dest) = dest {
+        let backups = install_backup_root(&dest);
+        if backups.exists() {
+            fs::remove_dir_all(&backups)
+                .map_err(|e| format!("Failed to remove {}: {}", backups.display(), e))?;
+        }
+    }
+    log::info!("factory_reset: remove_backups={}", remove_backups);
+    Ok(())
+}
+
+// Puts back the originals saved in `backup` and deletes the files that apply created, returning
+// how many of each.
+fn restore_backup(dest: &Path, backup: &Path) -> Result<(u64, u64), LauncherError> {
+    let raw = fs::read_to_string(backup.join("manifest.json"))?;
+    let manifest: BackupManifest = serde_json::from_str(&raw)?;
+    // Checked before anything is touched: an edited manifest must not reach outside the install.
+    let safe = |rels: &[String]| -> Result<Vec<PathBuf>, LauncherError> {
+        rels.iter()
+            .map(|rel| {
+                safe_relative_path(rel)
+                    .ok_or_else(|| format!("Backup manifest has an unsafe path: {}", rel).into())
+            })
+            .collect()
+    };
+    let replaced = safe(&manifest.replaced)?;
+    let created = safe(&manifest.created)?;
+    let mut restored = 0u64;
+    for rel in &replaced {
+        let original = backup.join(rel);
+        if !original.is_file() {
+            continue;
+        }
+        let target = resolve_dest_path(dest, rel);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&original, &target)
+            .map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+        restored += 1;
+    }
+    let mut removed = 0u64;
+    for rel in &created {
+        let target = resolve_dest_path(dest, rel);
+        if target.is_file() {
+            fs::remove_file(&target)
+                .map_err(|e| format!("Failed to remove {}: {}", target.display(), e))?;
+            removed += 1;
+        }
+    }
+    Ok((restored, removed))
+}
+
+#[tauri::command]
+fn revert_optimizations(
+    app_handle: tauri::AppHandle,
+    game_root: Option<String>,
+) -> Result<serde_json::Value, LauncherError> {
+    let dest = remembered_install_dir(&app_handle, game_root)?;
+    log::info!("revert_optimizations: dest={}", dest.display());
+    let backup = latest_install_backup(&dest)
+        .ok_or_else(|| format!("No optimization backup found under {}", dest.display()))?;
+    let (restored, removed) = restore_backup(&dest, &backup)?;
+    Ok(serde_json::json!({
+      "restored": restored,
+      "removed": removed,
+      "backup_used": backup.to_string_lossy().to_string()
+    }))
+}
+
+#[tauri::command]
+fn describe_layout(workshop_id: String) -> String {
+    let workshop_id = normalize_workshop_id(&workshop_id).unwrap_or(workshop_id);
+    let steam_root = steam_root();
+    let show = |p: Option<PathBuf>| match p {
+        Some(p) => p.to_string_lossy().to_string(),
+        None => "(not found)".to_string(),
+    };
+    let libs = parse_libraryfolders(&steam_root);
+    let mut out = format!("Steam root: {}\n", steam_root);
+    out.push_str("Libraries:\n");
+    for (idx, lib) in libs.iter().enumerate() {
+        let branch = if idx + 1 == libs.len() {
+            "└─"
+        } else {
+            "├─"
+        };
+        out.push_str(&format!("  {} {}\n", branch, lib.display()));
+    }
+    out.push_str(&format!(
+        "Project Zomboid install: {}\n",
+        show(pz_install_dir(&libs))
+    ));
+    out.push_str(&format!(
+        "Install backups: {}\n",
+        show(pz_install_dir(&libs).map(|dir| install_backup_root(&dir)))
+    ));
+    let workshop = find_workshop_item(&libs, &workshop_id).map(PathBuf::from);
+    out.push_str(&format!(
+        "Workshop item {}: {}\n",
+        workshop_id,
+        show(workshop.clone())
+    ));
+    if let Some(ws) = workshop {
+        out.push_str(&format!(
+            "  ├─ Cachedir: {}\n",
+            workshop_zomboid_root(&ws).display()
+        ));
+        out.push_str(&format!(
+            "  ├─ Optimization source: {}\n",
+            optimizations_source_root(&ws).display()
+        ));
+        out.push_str(&format!(
+            "  └─ Launcher log: {}\n",
+            launcher_log_path(&ws).display()
+        ));
+    }
+    out
+}
+
+#[tauri::command]
+fn resolve_game_root() -> Result<String, LauncherError> {
+    let steam_root = require_steam_root()?;
+    let p = pz_install_dir(&parse_libraryfolders(&steam_root))
+        .ok_or(LauncherError::PzInstallNotFound)?;
+    Ok(p.to_string_lossy().to_string())
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+// Picks the disk with the longest mount point containing `path`.
+fn available_space(path: &Path) -> Option<u64> {
+    let normalize = |p: &Path| p.to_string_lossy().replace('\\', "/").to_ascii_lowercase();
+    let target = normalize(path);
+    sysinfo::Disks::new_with_refreshed_list()
+        .iter()
+        .filter(|disk| target.starts_with(&normalize(disk.mount_point())))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn read_apply_history(path: &Path) -> Vec<ApplyRecord> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn record_apply(
+    real_workshop_path: &Path,
+    result: &Result<serde_json::Value, LauncherError>,
+    elapsed: Duration,
+) -> io::Result<()> {
+    let path = apply_history_path(real_workshop_path);
+    let mut history = read_apply_history(&path);
+    let count = |v: &serde_json::Value, key: &str| v[key].as_u64().unwrap_or(0);
+    history.push(match result {
+        Ok(v) => ApplyRecord {
+            timestamp: unix_now(),
+            success: true,
+            already: v["already"].as_bool().unwrap_or(false),
+            copied: count(v, "copied"),
+            replaced: count(v, "replaced"),
+            backed_up: count(v, "backed_up"),
+            duration_ms: elapsed.as_millis() as u64,
+            error: None,
+        },
+        Err(e) => ApplyRecord {
+            timestamp: unix_now(),
+            success: false,
+            already: false,
+            copied: 0,
+            replaced: 0,
+            backed_up: 0,
+            duration_ms: elapsed.as_millis() as u64,
+            error: Some(e.to_string()),
+        },
+    });
+    if history.len() > APPLY_HISTORY_LIMIT {
+        history.drain(..history.len() - APPLY_HISTORY_LIMIT);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&history).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+#[tauri::command]
+fn apply_history(workshop_path: String) -> Result<Vec<ApplyRecord>, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    Ok(read_apply_history(&apply_history_path(Path::new(
+        &workshop_path,
+    ))))
+}
+
+const PZ_INSTALL_MARKERS: &[&str] = &["ProjectZomboid64.exe", "ProjectZomboid64.json"];
+
+fn is_pz_install(dir: &Path) -> bool {
+    dir.join("media").is_dir() && PZ_INSTALL_MARKERS.iter().any(|m| dir.join(m).is_file())
+}
+
+fn resolve_apply_dest(libs: &[PathBuf], game_root: Option<&str>) -> Result<PathBuf, LauncherError> {
+    match game_root.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(root) => {
+            let dir = PathBuf::from(root);
+            if !is_pz_install(&dir) {
+                return Err(format!(
+                    "{} does not look like a Project Zomboid install",
+                    dir.display()
+                )
+                .into());
+            }
+            Ok(dir)
+        }
+        None => pz_install_dir(libs).ok_or(LauncherError::PzInstallNotFound),
+    }
+}
+
+// Folders the optimization pack writes into; a partial install can be missing them.
+const PZ_MEDIA_DIRS: &[&str] = &["media", "media/lua", "media/scripts", "media/maps"];
+
+fn missing_media_dirs(install_dir: &Path) -> Vec<String> {
+    PZ_MEDIA_DIRS
+        .iter()
+        .filter(|rel| !install_dir.join(rel).is_dir())
+        .map(|rel| rel.to_string())
+        .collect()
+}
+
+#[tauri::command]
+fn check_install_completeness(
+    app_handle: tauri::AppHandle,
+    game_root: Option<String>,
+) -> Result<InstallCompleteness, LauncherError> {
+    let install_dir = remembered_install_dir(&app_handle, game_root)?;
+    let missing = missing_media_dirs(&install_dir);
+    Ok(InstallCompleteness {
+        install_dir: install_dir.to_string_lossy().to_string(),
+        complete: missing.is_empty(),
+        missing,
+    })
+}
+
+// Async so the blocking folder picker runs off the main thread.
+#[tauri::command]
+async fn pick_game_root(app_handle: tauri::AppHandle) -> Result<Option<String>, LauncherError> {
+    let picked = app_handle
+        .dialog()
+        .file()
+        .set_title("Select your Project Zomboid install folder")
+        .blocking_pick_folder();
+    let dir = match picked {
+        Some(p) => p
+            .into_path()
+            .map_err(|e| LauncherError::Other(e.to_string()))?,
+        None => return Ok(None),
+    };
+    if !is_pz_install(&dir) {
+        return Err(format!(
+            "{} does not look like a Project Zomboid install",
+            dir.display()
+        )
+        .into());
+    }
+    Ok(Some(dir.to_string_lossy().to_string()))
+}
+
+fn remember_applied_hash(app_handle: &tauri::AppHandle, workshop_path: &str) {
+    let manifest_path = optimization_manifest_path(Path::new(workshop_path));
+    if let Ok(manifest) = read_manifest(&manifest_path) {
+        let _ = settings::update_settings(app_handle, |settings| {
+            settings.optimizations_applied_hash = Some(aggregate_hash(&manifest.entries))
+        });
+    }
+}
+
+#[tauri::command]
+fn apply_optimizations(
+    app_handle: tauri::AppHandle,
+    workshop_path: String,
+    game_root: Option<String>,
+    verify_copies: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<serde_json::Value, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    validate_workshop_path(Path::new(&workshop_path))?;
+    let game_root = remembered_game_root(&app_handle, game_root);
+    let started = Instant::now();
+    let src = optimizations_source_root(Path::new(&workshop_path));
+    log::info!("apply_optimizations: source={}", src.display());
+    if dry_run.unwrap_or(false) {
+        if !src.exists() {
+            return Err(LauncherError::OptimizationsSourceMissing(
+                src.display().to_string(),
+            ));
+        }
+        let dest = resolve_apply_dest(
+            &parse_libraryfolders(&require_steam_root()?),
+            game_root.as_deref(),
+        )?;
+        let plan = plan_copy(&src, &dest)?;
+        return Ok(serde_json::json!({
+          "dry_run": true,
+          "would_create": plan.would_create,
+          "would_replace": plan.would_replace,
+          "identical": plan.identical,
+          "source": src.to_string_lossy().to_string(),
+          "dest": dest.to_string_lossy().to_string()
+        }));
+    }
+    let bytes_total: u64 = list_files_recursive(&src)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|f| fs::metadata(f).ok())
+        .map(|m| m.len())
+        .sum();
+    let mut bytes_done: u64 = 0;
+    let applied_hash = settings::load_settings(app_handle.clone()).optimizations_applied_hash;
+    let result = run_apply_optimizations(
+        &workshop_path,
+        game_root.as_deref(),
+        applied_hash.as_deref(),
+        verify_copies.unwrap_or(false),
+        &mut |done, total, rel, bytes| {
+            bytes_done += bytes;
+            let _ = app_handle.emit(
+                "optimizations-progress",
+                serde_json::json!({
+                    "files_done": done,
+                    "files_total": total,
+                    "bytes_done": bytes_done,
+                    "bytes_total": bytes_total,
+                    "current_file": rel.to_string_lossy().replace('\\', "/"),
+                }),
+            );
+        },
+    );
+    match &result {
+        Ok(value) => log::info!("apply_optimizations: {}", value),
+        Err(e) => log::error!("apply_optimizations: {}", e),
+    }
+    if result.is_ok() {
+        remember_applied_hash(&app_handle, &workshop_path);
+    }
+    if let Ok(value) = &result {
+        let _ = app_handle.emit(
+            "optimizations-done",
+            serde_json::json!({
+                "copied": value["copied"].as_u64().unwrap_or(0),
+                "replaced": value["replaced"].as_u64().unwrap_or(0),
+                "skipped": value["skipped"].as_u64().unwrap_or(0),
+            }),
+        );
+    }
+    let _ = record_apply(Path::new(&workshop_path), &result, started.elapsed());
+    report_outcome(
+        &app_handle,
+        "apply-optimizations",
+        String::new(),
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn probe_writable(dir: &Path) -> io::Result<()> {
+    let probe = dir.join(format!(".13p-write-probe-{}", std::process::id()));
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+fn run_apply_optimizations(
+    workshop_path: &str,
+    game_root: Option<&str>,
+    applied_hash: Option<&str>,
+    verify_copies: bool,
+    on_progress: &mut dyn FnMut(usize, usize, &Path, u64),
+) -> Result<serde_json::Value, LauncherError> {
+    let steam_root = require_steam_root()?;
+    // Source: <workshop>\mods\13thPandemic\ProjectZomboid
+    let src = optimizations_source_root(Path::new(&workshop_path));
+    if !src.exists() {
+        return Err(LauncherError::OptimizationsSourceMissing(
+            src.display().to_string(),
+        ));
+    }
+    let dest = resolve_apply_dest(&parse_libraryfolders(&steam_root), game_root)?;
+    let missing = missing_media_dirs(&dest);
+    if !missing.is_empty() {
+        return Err(format!(
+            "Project Zomboid install appears incomplete (missing {}). Verify the game files in Steam before applying optimizations.",
+            missing.join(", ")
+        ).into());
+    }
+    let manifest_path = optimization_manifest_path(Path::new(&workshop_path));
+
+    let mut stats = ApplyStats::default();
+    if optimizations_applied(&src, &dest, &manifest_path, applied_hash, &mut stats)? {
+        return Ok(serde_json::json!({
+          "already": true,
+          "applied": false,
+          "stats": stats,
+          "source": src.to_string_lossy().to_string(),
+          "dest": dest.to_string_lossy().to_string(),
+          "manifest": manifest_path.to_string_lossy().to_string()
+        }));
+    }
+
+    let bytes_required: u64 = list_files_recursive(&src)?
+        .iter()
+        .map(|f| fs::metadata(f).map(|m| m.len()).unwrap_or(0))
+        .sum();
+    if let Some(available) = available_space(&dest) {
+        if available < bytes_required {
+            return Err(format!(
+                "Not enough disk space: need {}, have {}",
+                format_bytes(bytes_required),
+                format_bytes(available)
+            )
+            .into());
+        }
+    }
+
+    // Steam under Program Files is often read-only without elevation; fail before any copying.
+    if let Err(err) = probe_writable(&dest) {
+        return Err(match err.kind() {
+            io::ErrorKind::PermissionDenied => {
+                LauncherError::InstallNotWritable(format!("Cannot write to {}", dest.display()))
+            }
+            _ => err.into(),
+        });
+    }
+
+    let backup_root = install_backup_root(&dest).join(unix_now().to_string());
+    let copy_started = Instant::now();
+    let report = copy_dir_replace(&src, &dest, Some(&backup_root), verify_copies, on_progress)
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::PermissionDenied => LauncherError::InstallNotWritable(err.to_string()),
+            _ => err.into(),
+        })?;
+    let copy_elapsed = copy_started.elapsed();
+    if !report.replaced_files.is_empty() || !report.created_files.is_empty() {
+        write_backup_manifest(
+            &backup_root,
+            report.replaced_files.clone(),
+            report.created_files.clone(),
+        )?;
+    }
+    stats.copied = report.copied + report.replaced;
+    let entries = build_manifest(&src)?;
+    write_manifest(&manifest_path, &entries)?;
+    let bytes_copied: u64 = report
+        .created_files
+        .iter()
+        .chain(&report.replaced_files)
+        .filter_map(|rel| fs::metadata(src.join(rel)).ok())
+        .map(|meta| meta.len())
+        .sum();
+    let throughput = bytes_copied * 1000 / (copy_elapsed.as_millis() as u64).max(1);
+    let av_warning = if copy_elapsed >= SLOW_COPY_MIN_DURATION
+        && throughput < SLOW_COPY_BYTES_PER_SEC
+        && defender_realtime_enabled()
+    {
+        Some(format!(
+            "Real-time antivirus may be slowing this down. Consider adding an exclusion for {}",
+            dest.display()
+        ))
+    } else {
+        None
+    };
+    let truncated = report.created_files.len() > CHANGED_FILES_LIMIT
+        || report.replaced_files.len() > CHANGED_FILES_LIMIT;
+    let copied_files: Vec<&String> = report
+        .created_files
+        .iter()
+        .take(CHANGED_FILES_LIMIT)
+        .collect();
+    let replaced_files: Vec<&String> = report
+        .replaced_files
+        .iter()
+        .take(CHANGED_FILES_LIMIT)
+        .collect();
+    Ok(serde_json::json!({
+      "already": false,
+      "applied": true,
+      "stats": stats,
+      "copied": report.copied,
+      "replaced": report.replaced,
+      "skipped": report.skipped,
+      "copied_files": copied_files,
+      "replaced_files": replaced_files,
+      "truncated": truncated,
+      "backed_up": report.backed_up,
+      "rewritten": report.rewritten,
+      "throughput_bytes_per_sec": throughput,
+      "bytes_required": bytes_required,
+      "av_warning": av_warning,
+      "source": src.to_string_lossy().to_string(),
+      "dest": dest.to_string_lossy().to_string(),
+      "backup_root": backup_root.to_string_lossy().to_string(),
+      "manifest": manifest_path.to_string_lossy().to_string()
+    }))
+}
+
+// Rejects server-supplied paths that could escape the install folder.
+fn safe_relative_path(rel: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(rel.replace('\\', "/"));
+    let normal = path
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)));
+    if normal && path.components().next().is_some() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+// Hashes shipped inside the launcher, so a tampered listing or download host can't get
+// arbitrary files written into the install.
+fn pinned_required_files() -> HashMap<String, String> {
+    serde_json::from_str::<serde_json::Value>(include_str!("../required-files.json"))
+        .ok()
+        .and_then(|v| v["files"].as_object().cloned())
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|(rel, hash)| {
+                    Some((rel.replace('\\', "/"), hash.as_str()?.to_ascii_lowercase()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// The server publishes `{ "base_url": "...", "files": { "<relpath>": "<sha256>" } }`; only the
+// listed files are touched so the client passes validation without over-applying.
+#[tauri::command]
+async fn apply_required_files(
+    app_handle: tauri::AppHandle,
+    workshop_path: String,
+    game_root: Option<String>,
+) -> Result<serde_json::Value, LauncherError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        run_apply_required_files(app_handle, workshop_path, game_root)
+    })
+    .await
+    .map_err(|e| LauncherError::Other(e.to_string()))?
+}
+
+fn run_apply_required_files(
+    app_handle: tauri::AppHandle,
+    workshop_path: String,
+    game_root: Option<String>,
+) -> Result<serde_json::Value, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let dest = remembered_install_dir(&app_handle, game_root)?;
+    let src = optimizations_source_root(Path::new(&workshop_path));
+    let pinned = pinned_required_files();
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(NET_TIMEOUT)
+        .timeout(NET_TIMEOUT)
+        .build()?;
+    let listing: serde_json::Value = client
+        .get(REQUIRED_FILES_URL)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| format!("Failed to fetch required file list: {}", e))?;
+    let files = listing["files"]
+        .as_object()
+        .ok_or_else(|| "Required file list has no \"files\" map".to_string())?;
+    let base_url = listing["base_url"]
+        .as_str()
+        .map(|u| u.trim_end_matches('/'));
+    if let Some(base) = base_url {
+        if !base.starts_with("https://") {
+            return Err(format!("Refusing non-HTTPS download location {}", base).into());
+        }
+    }
+    let backup_root = install_backup_root(&dest).join(unix_now().to_string());
+
+    let mut compliant: u64 = 0;
+    let mut fixed = Vec::new();
+    let mut replaced = Vec::new();
+    let mut created = Vec::new();
+    let mut unresolved = Vec::new();
+    for (rel, hash) in files {
+        let required = hash.as_str().unwrap_or_default().to_ascii_lowercase();
+        let rel_path = match safe_relative_path(rel) {
+            Some(p) => p,
+            None => {
+                unresolved.push(rel.clone());
+                continue;
+            }
+        };
+        // The listing only picks which files are required; their contents must match the hashes
+        // bundled with this launcher build.
+        if pinned.get(&rel.replace('\\', "/")) != Some(&required) {
+            log::error!("required file {} does not match the pinned hash", rel);
+            unresolved.push(rel.clone());
+            continue;
+        }
+        let target = dest.join(&rel_path);
+        if target.is_file() && file_sha256(&target)? == required {
+            compliant += 1;
+            continue;
+        }
+        let local = src.join(&rel_path);
+        let contents = if local.is_file() && file_sha256(&local)? == required {
+            Some(fs::read(&local)?)
+        } else if let Some(base) = base_url {
+            client
+                .get(format!("{}/{}", base, rel.replace('\\', "/")))
+                .send()
+                .and_then(|r| r.error_for_status())
+                .and_then(|r| r.bytes())
+                .ok()
+                .map(|body| body[..].to_vec())
+                .filter(|body| sha256_hex(body) == required)
+        } else {
+            None
+        };
+        let contents = match contents {
+            Some(c) => c,
+            None => {
+                unresolved.push(rel.clone());
+                continue;
+            }
+        };
+        if target.exists() {
+            let backup_path = backup_root.join(&rel_path);
+            if !backup_path.exists() {
+                if let Some(parent) = backup_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&target, &backup_path)?;
+            }
+            replaced.push(rel_path.to_string_lossy().replace('\\', "/"));
+        } else {
+            created.push(rel_path.to_string_lossy().replace('\\', "/"));
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, contents)?;
+        fixed.push(rel.clone());
+    }
+    if !fixed.is_empty() {
+        write_backup_manifest(&backup_root, replaced, created)?;
+    }
+    Ok(serde_json::json!({
+      "compliant": unresolved.is_empty(),
+      "already_compliant": compliant,
+      "brought_into_compliance": fixed,
+      "unresolved": unresolved,
+      "dest": dest.to_string_lossy().to_string(),
+      "backup_root": backup_root.to_string_lossy().to_string()
+    }))
+}
+
+// Hashes the files the player actually has installed, for admins to cross-reference.
+#[tauri::command]
+fn export_client_manifest(
+    app_handle: tauri::AppHandle,
+    workshop_path: String,
+) -> Result<String, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let ws = PathBuf::from(&workshop_path);
+    let dest = remembered_install_dir(&app_handle, None)?;
+    let expected = match read_manifest(&optimization_manifest_path(&ws)) {
+        Ok(manifest) => manifest.entries,
+        Err(_) => build_manifest(&optimizations_source_root(&ws))?,
+    };
+    if expected.is_empty() {
+        return Err("No applied optimization files to export".into());
+    }
+    let mut installed = Vec::with_capacity(expected.len());
+    let mut missing = Vec::new();
+    for entry in &expected {
+        let path = dest.join(Path::new(&entry.path));
+        if !path.is_file() {
+            missing.push(entry.path.clone());
+            continue;
+        }
+        installed.push(ManifestEntry {
+            path: entry.path.clone(),
+            size: fs::metadata(&path)?.len(),
+            hash: file_sha256(&path)?,
+        });
+    }
+    let report = serde_json::json!({
+      "generated_at": unix_now(),
+      "tree_hash": tree_hash(&installed),
+      "files": installed,
+      "missing": missing
+    });
+    serde_json::to_string_pretty(&report).map_err(LauncherError::from)
+}
+
+#[tauri::command]
+fn check_cachedir_junction(workshop_path: String) -> CachedirJunction {
+    cachedir_junction(Path::new(&workshop_path))
+}
+
+#[tauri::command]
+fn repair_cachedir_junction(
+    workshop_path: String,
+    new_target: String,
+) -> Result<(), LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let target = PathBuf::from(&new_target);
+    if !target.is_dir() {
+        return Err(format!("Junction target {} does not exist", target.display()).into());
+    }
+    let current = cachedir_junction(Path::new(&workshop_path));
+    let link = PathBuf::from(&current.path);
+    if current.is_junction {
+        // Junctions are removed like empty directories; this leaves the old target untouched
+        fs::remove_dir(&link)
+            .or_else(|_| fs::remove_file(&link))
+            .map_err(|e| format!("Failed to remove junction {}: {}", link.display(), e))?;
+    } else if link.exists() {
+        return Err(format!(
+            "{} is a regular folder, not a junction; move it aside before repairing",
+            link.display()
+        )
+        .into());
+    }
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    #[cfg(windows)]
+    {
+        let status = Command::new("cmd")
+            .arg("/C")
+            .arg("mklink")
+            .arg("/J")
+            .arg(&link)
+            .arg(&target)
+            .status()?;
+        if !status.success() {
+            return Err(format!("mklink /J failed for {}", link.display()).into());
+        }
+    }
+    #[cfg(not(windows))]
+    std::os::unix::fs::symlink(&target, &link)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn filesystem_case_sensitivity(path: String) -> bool {
+    fs_case_sensitive(Path::new(&path)).unwrap_or(false)
+}
+
+#[cfg(windows)]
+#[tauri::command]
+fn defender_realtime_enabled() -> bool {
+    // DisableRealtimeMonitoring is only written when protection has been turned off.
+    RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey("SOFTWARE\\Microsoft\\Windows Defender\\Real-Time Protection")
+        .map(|key| {
+            key.get_value::<u32, _>("DisableRealtimeMonitoring")
+                .unwrap_or(0)
+                == 0
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+#[tauri::command]
+fn defender_realtime_enabled() -> bool {
+    false
+}
+
+fn verify_install(
+    entries: &[ManifestEntry],
+    dst_root: &Path,
+    on_file: &mut dyn FnMut(usize, usize, &Path),
+) -> io::Result<Vec<String>> {
+    let mut mismatched = Vec::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        let dest_path = resolve_dest_path(dst_root, Path::new(&entry.path));
+        let ok = match fs::metadata(&dest_path) {
+            Ok(meta) => meta.len() == entry.size && file_sha256(&dest_path)? == entry.hash,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => false,
+            Err(err) => return Err(err),
+        };
+        if !ok {
+            mismatched.push(entry.path.clone());
+        }
+        on_file(idx + 1, entries.len(), Path::new(&entry.path));
+    }
+    Ok(mismatched)
+}
+
+fn rollback_copy(dst_root: &Path, rollback_root: &Path, created: &[PathBuf]) -> io::Result<()> {
+    for rel in created {
+        let d = dst_root.join(rel);
+        if d.exists() {
+            fs::remove_file(&d)?;
+        }
+    }
+    if rollback_root.exists() {
+        for saved in list_files_recursive(rollback_root)? {
+            let rel = saved.strip_prefix(rollback_root).unwrap();
+            fs::copy(&saved, resolve_dest_path(dst_root, rel))?;
+        }
+    }
+    Ok(())
+}
+
+// Same apply as apply_optimizations, then every file is re-hashed in the install and the apply
+// is undone from its backup if any of them came out wrong.
+#[tauri::command]
+fn apply_and_verify(
+    app_handle: tauri::AppHandle,
+    workshop_path: String,
+) -> Result<serde_json::Value, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let game_root = remembered_game_root(&app_handle, None);
+    let applied_hash = settings::load_settings(app_handle.clone()).optimizations_applied_hash;
+    let emit_progress = |phase: &str, done: usize, total: usize, file: &Path| {
+        let _ = app_handle.emit(
+            "apply-verify-progress",
+            serde_json::json!({
+                "phase": phase,
+                "done": done,
+                "total": total,
+                "file": file.to_string_lossy().replace('\\', "/"),
+            }),
+        );
+    };
+
+    // A failed copy is already rolled back by copy_dir_replace.
+    let applied = run_apply_optimizations(
+        &workshop_path,
+        game_root.as_deref(),
+        applied_hash.as_deref(),
+        false,
+        &mut |done, total, rel, _| emit_progress("apply", done, total, rel),
+    )?;
+    let src = optimizations_source_root(Path::new(&workshop_path));
+    let dest = PathBuf::from(applied["dest"].as_str().unwrap_or_default());
+    let manifest_path = optimization_manifest_path(Path::new(&workshop_path));
+    let entries = match read_manifest(&manifest_path) {
+        Ok(manifest) => manifest.entries,
+        Err(_) => build_manifest(&src)?,
+    };
+    let backup_root = applied["backup_root"].as_str().map(PathBuf::from);
+
+    let mismatched = verify_install(&entries, &dest, &mut |done, total, rel| {
+        emit_progress("verify", done, total, rel)
+    })?;
+    if !mismatched.is_empty() {
+        let mut rolled_back = false;
+        if let Some(backup) = backup_root.filter(|b| b.join("manifest.json").is_file()) {
+            restore_backup(&dest, &backup)?;
+            fs::remove_dir_all(&backup)?;
+            rolled_back = true;
+        }
+        return Ok(serde_json::json!({
+          "applied": false,
+          "verified": false,
+          "rolled_back": rolled_back,
+          "mismatched": mismatched,
+          "source": src.to_string_lossy().to_string(),
+          "dest": dest.to_string_lossy().to_string()
+        }));
+    }
+
+    remember_applied_hash(&app_handle, &workshop_path);
+    Ok(serde_json::json!({
+      "applied": true,
+      "verified": true,
+      "rolled_back": false,
+      "copied": applied["copied"].as_u64().unwrap_or(0),
+      "replaced": applied["replaced"].as_u64().unwrap_or(0),
+      "skipped": applied["skipped"].as_u64().unwrap_or(0),
+      "backed_up": applied["backed_up"].as_u64().unwrap_or(0),
+      "source": src.to_string_lossy().to_string(),
+      "dest": dest.to_string_lossy().to_string(),
+      "backup_root": backup_root.map(|b| b.to_string_lossy().to_string()),
+      "manifest": manifest_path.to_string_lossy().to_string()
+    }))
+}
+
+// Steam can only start in offline mode for an account whose credentials it remembered.
+#[tauri::command]
+fn offline_readiness(app_handle: tauri::AppHandle, workshop_path: String) -> OfflineReadiness {
+    let steam_root = steam_root();
+    let steam_offline_ready = parse_loginusers(&steam_root)
+        .iter()
+        .any(|user| user.most_recent && user.remember_password);
+    let dest = remembered_install_dir(&app_handle, None).ok();
+    let files_applied = match (&dest, workshop_path.is_empty()) {
+        (Some(dest), false) => {
+            let src = optimizations_source_root(Path::new(&workshop_path));
+            let manifest_path = optimization_manifest_path(Path::new(&workshop_path));
+            src.exists()
+                && optimizations_applied(
+                    &src,
+                    dest,
+                    &manifest_path,
+                    None,
+                    &mut ApplyStats::default(),
+                )
+                .unwrap_or(false)
+        }
+        _ => false,
+    };
+    let saves_present = !workshop_path.is_empty()
+        && fs::read_dir(workshop_zomboid_root(Path::new(&workshop_path)).join("Saves"))
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false);
+    OfflineReadiness {
+        steam_offline_ready,
+        pz_installed: dest.is_some(),
+        files_applied,
+        saves_present,
+    }
+}
+
+#[tauri::command]
+fn check_optimizations(
+    app_handle: tauri::AppHandle,
+    workshop_path: String,
+) -> Result<bool, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let src = optimizations_source_root(Path::new(&workshop_path));
+    if !src.exists() {
+        return Err(LauncherError::OptimizationsSourceMissing(
+            src.display().to_string(),
+        ));
+    }
+    let dest = remembered_install_dir(&app_handle, None)?;
+    let manifest_path = optimization_manifest_path(Path::new(&workshop_path));
+    let settings = settings::load_settings(app_handle);
+    optimizations_applied(
+        &src,
+        &dest,
+        &manifest_path,
+        settings.optimizations_applied_hash.as_deref(),
+        &mut ApplyStats::default(),
+    )
+    .map_err(LauncherError::from)
+}
+
+// Read-only counterpart of check_optimizations: the manifest is never rewritten here.
+#[tauri::command]
+fn optimization_status(app_handle: tauri::AppHandle, workshop_path: String) -> OptStatus {
+    let ws = Path::new(&workshop_path);
+    let src = optimizations_source_root(ws);
+    let mut status = OptStatus {
+        source_found: !workshop_path.is_empty() && src.exists(),
+        applied: false,
+        files_total: 0,
+        files_differing: 0,
+    };
+    if !status.source_found {
+        return status;
+    }
+    let manifest_path = optimization_manifest_path(ws);
+    let recorded = read_manifest(&manifest_path).ok().filter(|manifest| {
+        fs::metadata(&manifest_path)
+            .and_then(|meta| meta.modified())
+            .and_then(|written_at| manifest_matches_src(&manifest.entries, &src, written_at))
+            .unwrap_or(false)
+    });
+    let entries = match recorded {
+        Some(manifest) => manifest.entries,
+        None => match build_manifest(&src) {
+            Ok(entries) => entries,
+            Err(_) => return status,
+        },
+    };
+    status.files_total = entries.len();
+    status.files_differing = match remembered_install_dir(&app_handle, None) {
+        Ok(dest) => {
+            let mut stats = ApplyStats::default();
+            entries
+                .iter()
+                .filter(|entry| !dest_entry_matches(entry, &dest, &mut stats).unwrap_or(false))
+                .count()
+        }
+        Err(_) => entries.len(),
+    };
+    status.applied = status.files_total > 0 && status.files_differing == 0;
+    status
+}
+
+#[tauri::command]
+fn open_launcher_log(workshop_path: String) -> Result<String, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let log_path = launcher_log_path(Path::new(&workshop_path));
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if !log_path.exists() {
+        fs::write(&log_path, "")?;
+    }
+    open::that(&log_path)?;
+    Ok(log_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn append_launcher_log(workshop_path: String, entry: String) -> Result<(), LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let log_path = launcher_log_path(Path::new(&workshop_path));
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)?;
+    file.write_all(entry.as_bytes())?;
+    Ok(())
+}
+
+#[tauri::command]
+fn write_launcher_log(workshop_path: String, contents: String) -> Result<(), LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let log_path = launcher_log_path(Path::new(&workshop_path));
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&log_path, contents)?;
+    Ok(())
+}
+
+fn read_launch_times(path: &Path) -> Vec<u64> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn record_launch_time(real_workshop_path: &Path, ms: u64) -> io::Result<()> {
+    let path = launch_times_path(real_workshop_path);
+    let mut times = read_launch_times(&path);
+    times.push(ms);
+    if times.len() > LAUNCH_TIMES_LIMIT {
+        times.drain(..times.len() - LAUNCH_TIMES_LIMIT);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(
+        path,
+        serde_json::to_string(&times).map_err(io::Error::other)?,
+    )
+}
+
+#[tauri::command]
+fn average_launch_time(workshop_path: String) -> Result<Option<u64>, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let times = read_launch_times(&launch_times_path(Path::new(&workshop_path)));
+    if times.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(times.iter().sum::<u64>() / times.len() as u64))
+}
+
+// PZ keeps its settings as .ini files at the top of the cachedir; a crash can truncate them.
+fn pz_config_files(cachedir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(cachedir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.is_file()
+                        && p.extension()
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("ini"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn snapshot_pz_config(real_workshop_path: &Path) -> io::Result<u64> {
+    let files = pz_config_files(&workshop_zomboid_root(real_workshop_path));
+    if files.is_empty() {
+        return Ok(0);
+    }
+    let snapshot = config_snapshot_root(real_workshop_path);
+    if snapshot.exists() {
+        fs::remove_dir_all(&snapshot)?;
+    }
+    fs::create_dir_all(&snapshot)?;
+    for file in &files {
+        if let Some(name) = file.file_name() {
+            fs::copy(file, snapshot.join(name))?;
+        }
+    }
+    Ok(files.len() as u64)
+}
+
+#[tauri::command]
+fn restore_pz_config(workshop_path: String) -> Result<u64, LauncherError> {
+    if workshop_path.is_empty() {
+        return Err(LauncherError::WorkshopPathEmpty);
+    }
+    let snapshot = config_snapshot_root(Path::new(&workshop_path));
+    if !snapshot.exists() {
+        return Err("No pre-launch config snapshot found".into());
+    }
+    let cachedir = workshop_zomboid_root(Path::new(&workshop_path));
+    let mut restored: u64 = 0;
+    for file in pz_config_files(&snapshot) {
+        if let Some(name) = file.file_name() {
+            fs::copy(&file, cachedir.join(name))?;
+            restored += 1;
+        }
+    }
+    Ok(restored)
+}
+
+// The JVM writes hs_err_pid<pid>.log into the working directory when the game crashes hard;
+// with a known pid only that session's log counts.
+fn session_crashed(search_dirs: &[PathBuf], since: SystemTime, pid: Option<u32>) -> bool {
+    let prefix = match pid {
+        Some(pid) => format!("hs_err_pid{}", pid),
+        None => "hs_err_pid".to_string(),
+    };
+    search_dirs.iter().any(|dir| {
+        fs::read_dir(dir)
+            .map(|entries| {
+                entries.filter_map(|e| e.ok()).any(|e| {
+                    let name = e.file_name().to_string_lossy().to_ascii_lowercase();
+                    name.starts_with(&prefix)
+                        && e.metadata()
+                            .and_then(|m| m.modified())
+                            .is_ok_and(|t| t >= since)
+                })
+            })
+            .unwrap_or(false)
+    })
+}
+
+// Windows flags a top-level window as hung once it stops pumping messages for ~5 seconds.
+// Returns `None` when the process has no visible window to ask about.
+#[cfg(windows)]
+fn process_hung(pid: u32) -> Option<bool> {
+    type Hwnd = isize;
+    #[link(name = "user32")]
+    extern "system" {
+        fn EnumWindows(
+            callback: unsafe extern "system" fn(Hwnd, isize) -> i32,
+            lparam: isize,
+        ) -> i32;
+        fn GetWindowThreadProcessId(hwnd: Hwnd, pid: *mut u32) -> u32;
+        fn IsWindowVisible(hwnd: Hwnd) -> i32;
+        fn IsHungAppWindow(hwnd: Hwnd) -> i32;
+    }
+    struct Search {
+        pid: u32,
+        hung: Option<bool>,
+    }
+    unsafe extern "system" fn visit(hwnd: Hwnd, lparam: isize) -> i32 {
+        let search = &mut *(lparam as *mut Search);
+        let mut owner: u32 = 0;
+        GetWindowThreadProcessId(hwnd, &mut owner);
+        if owner != search.pid || IsWindowVisible(hwnd) == 0 {
+            return 1;
+        }
+        search.hung = Some(IsHungAppWindow(hwnd) != 0);
+        0
+    }
+    let mut search = Search { pid, hung: None };
+    unsafe {
+        EnumWindows(visit, &mut search as *mut Search as isize);
+    }
+    search.hung
+}
+
+#[cfg(not(windows))]
+fn process_hung(_pid: u32) -> Option<bool> {
+    None
+}
+
+// Native game launchers, in order of preference.
+#[cfg(windows)]
+const PZ_EXE_NAMES: &[&str] = &["ProjectZomboid64.exe"];
+#[cfg(not(windows))]
+const PZ_EXE_NAMES: &[&str] = &["ProjectZomboid64", "ProjectZomboid64.sh"];
+
+// The launcher owns these so players always land on the modpack server with its cachedir.
+const RESERVED_LAUNCH_ARGS: &[&str] = &["-connect", "-cachedir", "-port"];
+const LAUNCH_ARG_METACHARS: &[char] = &['&', '|', ';', '<', '>', '^', '`', '$', '"', '\n', '\r'];
+
+fn pz_exe(install_dir: &Path) -> Option<PathBuf> {
+    PZ_EXE_NAMES
+        .iter()
+        .map(|name| install_dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn validate_extra_args(args: Vec<String>) -> Result<Vec<String>, LauncherError> {
+    let mut valid = Vec::new();
+    for arg in args {
+        let arg = arg.trim().to_string();
+        if arg.is_empty() {
+            continue;
+        }
+        if arg.contains(LAUNCH_ARG_METACHARS) {
+            return Err(LauncherError::InvalidLaunchArg(arg));
+        }
+        let name = arg
+            .split('=')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if RESERVED_LAUNCH_ARGS.contains(&name.as_str()) {
+            return Err(LauncherError::InvalidLaunchArg(arg));
+        }
+        valid.push(arg);
+    }
+    Ok(valid)
+}
+
+// The 32-bit launch option and older installs run one of the other two; the last two are the
+// native Linux launch scripts.
+const PZ_PROCESS_NAMES: &[&str] = &[
+    "ProjectZomboid64.exe",
+    "ProjectZomboid32.exe",
+    "ProjectZomboid.exe",
+    "ProjectZomboid64",
+    "ProjectZomboid32",
+];
+
+// The Linux client runs as plain `steam`.
+const STEAM_PROCESS_NAMES: &[&str] = &["steam.exe", "steam"];
+const STEAM_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+const BIGPICTURE_ARGS: &[&str] = &["-gamepadui", "-bigpicture", "-tenfoot"];
+
+// Under Proton the process name is the Wine loader (or a truncated name), but the Windows exe
+// still shows up in the command line. Options are skipped so a -cachedir path can't match.
+fn is_pz_command_line(cmd: &[String]) -> bool {
+    cmd.iter().filter(|arg| !arg.starts_with('-')).any(|arg| {
+        let file = arg.rsplit(['/', '\\']).next().unwrap_or(arg);
+        PZ_PROCESS_NAMES
+            .iter()
+            .any(|name| file.eq_ignore_ascii_case(name))
+    })
+}
+
+fn pz_process(snapshot: &ProcessSnapshot) -> Option<&ProcessInfo> {
+    PZ_PROCESS_NAMES
+        .iter()
+        .find_map(|name| snapshot.find(name))
+        .or_else(|| {
+            snapshot
+                .processes
+                .iter()
+                .find(|p| is_pz_command_line(&p.cmd))
+        })
+}
+
+fn pz_process_running(snapshot: &ProcessSnapshot) -> Option<String> {
+    pz_process(snapshot).map(|p| p.name.clone())
+}
+
+fn steam_is_running(snapshot: &ProcessSnapshot) -> bool {
+    STEAM_PROCESS_NAMES
+        .iter()
+        .any(|name| snapshot.is_running(name))
+}
+
+#[tauri::command]
+fn steam_running(monitor: tauri::State<'_, ProcessMonitor>) -> bool {
+    steam_is_running(&monitor.snapshot())
+}
+
+// Polls the shared snapshot slowly instead of subscribing, so the monitor's fast polling thread
+// still only runs while a launch is being watched.
+fn watch_steam_status(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        let monitor = app_handle.state::<ProcessMonitor>().inner().clone();
+        let mut running = None;
+        loop {
+            let now = steam_is_running(&monitor.snapshot());
+            if running != Some(now) {
+                running = Some(now);
+                let _ = app_handle.emit(
+                    "steam-status-changed",
+                    serde_json::json!({ "running": now }),
+                );
+            }
+            thread::sleep(STEAM_STATUS_POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(windows)]
+fn bigpicture_flag_from_registry() -> bool {
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Software\\Valve\\Steam")
+        .and_then(|key| key.get_value::<u32, _>("BigPictureInForeground"))
+        .map(|v| v != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+fn bigpicture_flag_from_registry() -> bool {
+    false
+}
+
+fn bigpicture_active(snapshot: &ProcessSnapshot) -> bool {
+    if bigpicture_flag_from_registry() {
+        return true;
+    }
+    snapshot.processes.iter().any(|p| {
+        p.name.to_ascii_lowercase().starts_with("steam")
+            && p.cmd.iter().any(|arg| {
+                BIGPICTURE_ARGS
+                    .iter()
+                    .any(|flag| arg.eq_ignore_ascii_case(flag))
+            })
+    })
+}
+
+#[tauri::command]
+fn steam_bigpicture_active(monitor: tauri::State<'_, ProcessMonitor>) -> bool {
+    bigpicture_active(&monitor.snapshot())
+}
+
+#[tauri::command]
+fn process_monitor_status(monitor: tauri::State<'_, ProcessMonitor>) -> serde_json::Value {
+    serde_json::json!({
+      "interval_ms": monitor.interval().as_millis() as u64,
+      "subscribers": monitor.subscriber_count(),
+      "refreshes": monitor.refresh_count()
+    })
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum LaunchPhase {
+    Validating,
+    StartingSteam,
+    WaitingForSteam,
+    Applaunch,
+    WaitingForGame,
+    GameRunning,
+    GameExited,
+    Error,
+}
+
+// Every launch step goes out on `launch-event`; the older session events are derived from it.
+fn emit_launch_phase(app_handle: &tauri::AppHandle, phase: LaunchPhase, detail: serde_json::Value) {
+    if !matches!(phase, LaunchPhase::Error) {
+        if let Some(guard) = app_handle.try_state::<LaunchGuard>() {
+            *guard.last_phase.lock().unwrap() = Some(phase);
+        }
+    }
+    let legacy = match phase {
+        LaunchPhase::Applaunch => Some("pz-session-launched"),
+        LaunchPhase::GameExited => Some("pz-session-ended"),
+        _ => None,
+    };
+    if let Some(event) = legacy {
+        let _ = app_handle.emit(event, detail.clone());
+    }
+    let _ = app_handle.emit(
+        "launch-event",
+        serde_json::json!({ "phase": phase, "detail": detail }),
+    );
+}
+
+fn report_outcome(
+    app_handle: &tauri::AppHandle,
+    operation: &'static str,
+    phase: String,
+    error: Option<&LauncherError>,
+) {
+    let error_kind = error.map(LauncherError::kind);
+    telemetry::report(app_handle, move || {
+        let steam_root = detect_steam_root();
+        let pz_ready = steam_root
+            .as_deref()
+            .is_some_and(|root| pz_install_complete(&parse_libraryfolders(root)));
+        telemetry::Outcome::new(
+            operation,
+            pz_ready,
+            steam_root.is_some(),
+            &phase,
+            error_kind,
+        )
+    });
+}
+
+fn report_launch_outcome(app_handle: &tauri::AppHandle, error: Option<&LauncherError>) {
+    let phase = app_handle
+        .state::<LaunchGuard>()
+        .last_phase
+        .lock()
+        .unwrap()
+        .and_then(|phase| serde_json::to_value(phase).ok())
+        .and_then(|value| value.as_str().map(String::from))
+        .unwrap_or_default();
+    report_outcome(app_handle, "launch", phase, error);
+}
+
+// Older attempts are dropped rather than offered; the player has likely moved on.
+const PENDING_LAUNCH_MAX_AGE_SECS: u64 = 15 * 60;
+
+fn set_pending_launch(app_handle: &tauri::AppHandle, pending: Option<settings::PendingLaunch>) {
+    let _ = settings::update_settings(app_handle, |settings| settings.pending_launch = pending);
+}
+
+// Checked once the window has had time to register its listeners.
+fn offer_launch_resume(app_handle: tauri::AppHandle) {
+    let pending = match settings::load_settings(app_handle.clone()).pending_launch {
+        Some(pending) => pending,
+        None => return,
+    };
+    if unix_now().saturating_sub(pending.started_at) > PENDING_LAUNCH_MAX_AGE_SECS {
+        set_pending_launch(&app_handle, None);
+        return;
+    }
+    thread::spawn(move || {
+        thread::sleep(STEAM_STATUS_POLL_INTERVAL);
+        let monitor = app_handle.state::<ProcessMonitor>();
+        if steam_is_running(&monitor.snapshot()) {
+            log::info!(
+                "startup: offering to resume launch for {}",
+                pending.workshop_path
+            );
+            let _ = app_handle.emit(
+                "launch-resume-available",
+                serde_json::json!({
+                  "workshop_path": pending.workshop_path,
+                  "started_at": pending.started_at
+                }),
+            );
+        }
+    });
+}
+
+#[tauri::command]
+fn dismiss_pending_launch(app_handle: tauri::AppHandle) {
+    set_pending_launch(&app_handle, None);
+}
+
+// steam://run/<appid>//<args> passes launch options the same way -applaunch does.
+fn steam_run_url(appid: &str, args: &[String]) -> String {
+    let encoded: String = args
+        .join(" ")
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    format!("steam://run/{}//{}", appid, encoded)
+}
+
+struct LaunchJob {
+    appid: String,
+    workshop_path: String,
+    steam_root: String,
+    install_dir: PathBuf,
+    cachedir: PathBuf,
+    modpack_version: Option<String>,
+    extra_args: Vec<String>,
+    server: Option<ServerProfile>,
+    steam_start_timeout: Duration,
+    invoked_at: Instant,
+    // Starts the exe directly with -debug and captures its console output
+    debug_console: bool,
+}
+
+// Runs on a background thread so waiting for Steam never blocks IPC; failures are reported
+// through `pz-launch-failed` since the command has already returned.
+fn launch_and_watch(app_handle: tauri::AppHandle, job: LaunchJob) -> Result<(), LauncherError> {
+    let LaunchJob {
+        appid,
+        workshop_path,
+        steam_root,
+        install_dir,
+        cachedir,
+        modpack_version,
+        extra_args,
+        server,
+        steam_start_timeout: timeout,
+        invoked_at,
+        debug_console,
+    } = job;
+    let monitor = app_handle.state::<ProcessMonitor>().inner().clone();
+    let steam_exe = Path::new(&steam_root).join("steam.exe");
+    // A stale SteamPath (Steam moved) leaves no exe to spawn; the steam:// fallback below starts
+    // Steam on its own.
+    let steam_exe_found = steam_exe.exists();
+    if !steam_exe_found {
+        log::error!("play: steam.exe not found at {}", steam_exe.display());
+    }
+    // Ensure Steam is running before launching PZ
+    if steam_exe_found && !steam_is_running(&monitor.snapshot()) {
+        emit_launch_phase(
+            &app_handle,
+            LaunchPhase::StartingSteam,
+            serde_json::json!({ "steam_exe": steam_exe.to_string_lossy() }),
+        );
+        Command::new(&steam_exe).spawn()?;
+        let started = Instant::now();
+        // Slow machines can take well over a few seconds before -applaunch will be accepted
+        while !steam_is_running(&monitor.snapshot()) {
+            if started.elapsed() >= timeout {
+                log::error!("play: Steam did not start within {:?}", timeout);
+                return Err(LauncherError::SteamStartTimeout(timeout.as_secs()));
+            }
+            let progress = serde_json::json!({
+                "elapsed_ms": started.elapsed().as_millis() as u64,
+                "timeout_ms": timeout.as_millis() as u64,
+            });
+            emit_launch_phase(&app_handle, LaunchPhase::WaitingForSteam, progress.clone());
+            let _ = app_handle.emit("steam-starting", progress);
+            thread::sleep(PROCESS_POLL_INTERVAL);
+        }
+    }
+    let cachedir_native = native_path_string(&cachedir);
+    let _ = snapshot_pz_config(Path::new(&workshop_path));
+
+    // Launch Steam -> PZ with -cachedir and auto-connect using -applaunch
+    let cachedir_arg = format!("-cachedir={}", cachedir_native);
+    let mut game_args = vec![cachedir_arg];
+    if let Some(server) = &server {
+        game_args.push(format!("-connect={}", server.ip));
+        game_args.push(format!("-port={}", server.port));
+    }
+    game_args.extend(extra_args);
+    if debug_console {
+        game_args.push("-debug".to_string());
+    }
+    // Console capture needs the game as our own child process; -applaunch hands it to Steam.
+    let direct_exe = if debug_console {
+        let exe = pz_exe(&install_dir).ok_or_else(|| {
+            format!(
+                "A debug launch starts the game directly, but {} was not found in {}",
+                PZ_EXE_NAMES.join(" or "),
+                install_dir.display()
+            )
+        })?;
+        Some(exe)
+    } else if bigpicture_active(&monitor.snapshot()) {
+        // Big Picture can drop args passed through -applaunch, so start the game exe directly.
+        let exe = pz_exe(&install_dir);
+        if exe.is_none() {
+            let _ = app_handle.emit(
+                "pz-launch-warning",
+                "Steam Big Picture is active and may drop the server connect arguments.",
+            );
+        }
+        exe
+    } else {
+        None
+    };
+    let launch_mode = match (&direct_exe, steam_exe_found) {
+        (Some(_), _) => "direct",
+        (None, true) => "applaunch",
+        (None, false) => "protocol",
+    };
+    // Restart the debounce window from the actual -applaunch rather than the click.
+    *app_handle
+        .state::<LaunchGuard>()
+        .pending_since
+        .lock()
+        .unwrap() = Some(Instant::now());
+    if launch_mode == "protocol" {
+        let url = steam_run_url(&appid, &game_args);
+        log::info!("play: launching {}", url);
+        open::that(&url).map_err(|e| {
+            format!(
+                "steam.exe not found at {} and opening {} failed: {}",
+                steam_exe.display(),
+                url,
+                e
+            )
+        })?;
+    } else {
+        let mut command = match &direct_exe {
+            Some(exe) => {
+                let mut command = Command::new(exe);
+                if let Some(dir) = exe.parent() {
+                    command.current_dir(dir);
+                }
+                command
+            }
+            None => {
+                let mut command = Command::new(&steam_exe);
+                command.arg("-applaunch").arg(&appid);
+                command
+            }
+        };
+        command.args(&game_args);
+        let console_log = if debug_console {
+            let path = logging::pz_console_log_path(&app_handle)?;
+            let file = fs::File::create(&path)?;
+            command.stdout(file.try_clone()?).stderr(file);
+            Some(path)
+        } else {
+            None
+        };
+        log::info!("play: launching {:?}", command);
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => return Err(format!("Failed to launch Steam/PZ: {}", e).into()),
+        };
+        if let Some(path) = console_log {
+            let handle = app_handle.clone();
+            thread::spawn(move || {
+                let exit_code = child.wait().ok().and_then(|status| status.code());
+                let _ = handle.emit(
+                    "debug-log-ready",
+                    serde_json::json!({
+                        "path": path.to_string_lossy(),
+                        "exit_code": exit_code,
+                    }),
+                );
+            });
+        }
+    }
+    set_pending_launch(&app_handle, None);
+    if let Some(version) = &modpack_version {
+        let _ = fs::write(cachedir.join(CACHEDIR_VERSION_MARKER), version);
+    }
+
+    let launched_exe = direct_exe
+        .as_ref()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string());
+    let launch_payload = serde_json::json!({
+        "cachedir": cachedir_native.clone(),
+        "launch_mode": launch_mode,
+        "exe": launched_exe,
+        "steam_exe": steam_exe.to_string_lossy(),
+        "args": game_args,
+        "offline": server.is_none(),
+    });
+    emit_launch_phase(&app_handle, LaunchPhase::Applaunch, launch_payload);
+    emit_launch_phase(
+        &app_handle,
+        LaunchPhase::WaitingForGame,
+        serde_json::json!({ "cachedir": cachedir_native }),
+    );
+
+    let handle_for_exit = app_handle.clone();
+    let cachedir_for_exit = cachedir_native.clone();
+    let workshop_for_exit = workshop_path.clone();
+    let crash_dirs = vec![cachedir.clone(), install_dir];
+    let launched_at = SystemTime::now();
+    // A watcher left over from an earlier launch stops quietly; this one reports the session.
+    let token = app_handle.state::<SessionWatch>().replace();
+    let updates = monitor.subscribe();
+    thread::spawn(move || {
+        let mut exe = None;
+        let mut game_pid = None;
+        let mut found = false;
+        let mut time_to_process_ms = None;
+        let mut was_cancelled = false;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            if token.superseded.load(Ordering::SeqCst) {
+                return;
+            }
+            if token.cancelled.load(Ordering::SeqCst) {
+                was_cancelled = true;
+                break;
+            }
+            match updates.recv_timeout(remaining) {
+                Ok(snapshot) => {
+                    let process = pz_process(&snapshot);
+                    exe = process.map(|p| p.name.clone());
+                    game_pid = process.map(|p| p.pid);
+                    if exe.is_some() {
+                        found = true;
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
         }
-        if found {
-            loop {
-                watcher.refresh_processes();
-                if !watcher
-                    .processes()
-                    .values()
-                    .any(|p| p.name().eq_ignore_ascii_case(proc_name))
-                {
+        if found && !was_cancelled {
+            *handle_for_exit
+                .state::<LaunchGuard>()
+                .pending_since
+                .lock()
+                .unwrap() = None;
+            let ms = invoked_at.elapsed().as_millis() as u64;
+            time_to_process_ms = Some(ms);
+            let _ = record_launch_time(Path::new(&workshop_for_exit), ms);
+            let detected = serde_json::json!({
+                "cachedir": cachedir_for_exit,
+                "time_to_process_ms": ms,
+                "exe": exe,
+            });
+            let _ = handle_for_exit.emit("pz-process-detected", detected.clone());
+            emit_launch_phase(&handle_for_exit, LaunchPhase::GameRunning, detected);
+
+            let mut hung = false;
+            for snapshot in updates.iter() {
+                if token.superseded.load(Ordering::SeqCst) {
+                    return;
+                }
+                if token.cancelled.load(Ordering::SeqCst) {
+                    was_cancelled = true;
                     break;
                 }
-                thread::sleep(Duration::from_secs(2));
+                let pid = pz_process(&snapshot).map(|p| p.pid);
+                let pid = match pid {
+                    Some(pid) => pid,
+                    None => break,
+                };
+                let now_hung = process_hung(pid).unwrap_or(false);
+                if now_hung != hung {
+                    hung = now_hung;
+                    let event = if hung {
+                        "pz-not-responding"
+                    } else {
+                        "pz-responding"
+                    };
+                    let _ = handle_for_exit.emit(event, serde_json::json!({ "pid": pid }));
+                }
             }
         }
+        let exit_detected_at = unix_now();
+        let crashed =
+            found && !was_cancelled && session_crashed(&crash_dirs, launched_at, game_pid);
+        let snapshot = config_snapshot_root(Path::new(&workshop_for_exit));
+        if crashed && snapshot.exists() {
+            let _ = handle_for_exit.emit(
+                "pz-config-restore-available",
+                serde_json::json!({ "snapshot": snapshot.to_string_lossy().to_string() }),
+            );
+        }
         let payload = serde_json::json!({
             "found": found,
             "cachedir": cachedir_for_exit,
+            "time_to_process_ms": time_to_process_ms,
+            "exe": exe,
+            "pid": game_pid,
+            "crashed": crashed,
+            "cancelled": was_cancelled,
+            "exit_detected_at": exit_detected_at,
         });
-        let _ = handle_for_exit.emit("pz-session-ended", payload);
+        emit_launch_phase(&handle_for_exit, LaunchPhase::GameExited, payload);
     });
 
     Ok(())
 }
 
+#[tauri::command]
+fn kill_pz(monitor: tauri::State<'_, ProcessMonitor>) -> Result<u32, LauncherError> {
+    let killed = monitor.kill(PZ_PROCESS_NAMES);
+    log::info!("kill_pz: killed {} process(es)", killed);
+    Ok(killed)
+}
+
+#[tauri::command]
+fn system_memory_mb() -> u64 {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    system.total_memory() / (1024 * 1024)
+}
+
+const PZ_VM_CONFIG: &str = "ProjectZomboid64.json";
+
+// Rewrites the -Xmx entry in vmArgs (adding one if missing) and returns the previous value.
+fn replace_xmx(
+    config: &mut serde_json::Value,
+    max_mb: u32,
+) -> Result<Option<String>, LauncherError> {
+    let vm_args = config
+        .get_mut("vmArgs")
+        .and_then(|v| v.as_array_mut())
+        .ok_or_else(|| format!("{} has no vmArgs list", PZ_VM_CONFIG))?;
+    let new_arg = serde_json::Value::String(format!("-Xmx{}m", max_mb));
+    let existing = vm_args
+        .iter_mut()
+        .find(|arg| arg.as_str().is_some_and(|a| a.starts_with("-Xmx")));
+    match existing {
+        Some(arg) => {
+            let previous = arg.as_str().map(str::to_string);
+            *arg = new_arg;
+            Ok(previous)
+        }
+        None => {
+            vm_args.push(new_arg);
+            Ok(None)
+        }
+    }
+}
+
+#[tauri::command]
+fn set_pz_heap(
+    app_handle: tauri::AppHandle,
+    game_root: Option<String>,
+    max_mb: u32,
+) -> Result<serde_json::Value, LauncherError> {
+    if max_mb < 512 {
+        return Err(format!("A {} MB heap is too small for Project Zomboid", max_mb).into());
+    }
+    let dir = remembered_install_dir(&app_handle, game_root)?;
+    let path = dir.join(PZ_VM_CONFIG);
+    let raw = fs::read_to_string(&path)?;
+    let mut config: serde_json::Value = serde_json::from_str(&raw)?;
+    let previous = replace_xmx(&mut config, max_mb)?;
+    fs::write(&path, serde_json::to_string_pretty(&config)?)?;
+    log::info!(
+        "set_pz_heap: {} {:?} -> {}m",
+        path.display(),
+        previous,
+        max_mb
+    );
+    Ok(serde_json::json!({
+      "previous": previous,
+      "current": format!("-Xmx{}m", max_mb),
+      "path": path.to_string_lossy().to_string()
+    }))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn play(
+    app_handle: tauri::AppHandle,
+    launch_guard: tauri::State<'_, LaunchGuard>,
+    monitor: tauri::State<'_, ProcessMonitor>,
+    appid: String,
+    _workshop_id: String,
+    workshop_path: String,
+    extra_args: Option<Vec<String>>,
+    server: Option<ServerProfile>,
+    steam_start_timeout_secs: Option<u64>,
+    game_root: Option<String>,
+    connect_to_server: Option<bool>,
+    cachedir_override: Option<String>,
+) -> Result<String, LauncherError> {
+    start_launch(
+        app_handle,
+        launch_guard,
+        monitor,
+        appid,
+        workshop_path,
+        extra_args,
+        server,
+        steam_start_timeout_secs,
+        game_root,
+        connect_to_server,
+        cachedir_override,
+        false,
+    )
+}
+
+// Same as play, but runs PZ with -debug and writes its console to launcher-pz-console.log in
+// the log dir. Always starts the exe directly, since -applaunch gives no handle on the output.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn play_debug(
+    app_handle: tauri::AppHandle,
+    launch_guard: tauri::State<'_, LaunchGuard>,
+    monitor: tauri::State<'_, ProcessMonitor>,
+    appid: String,
+    _workshop_id: String,
+    workshop_path: String,
+    extra_args: Option<Vec<String>>,
+    server: Option<ServerProfile>,
+    steam_start_timeout_secs: Option<u64>,
+    game_root: Option<String>,
+    connect_to_server: Option<bool>,
+    cachedir_override: Option<String>,
+) -> Result<String, LauncherError> {
+    start_launch(
+        app_handle,
+        launch_guard,
+        monitor,
+        appid,
+        workshop_path,
+        extra_args,
+        server,
+        steam_start_timeout_secs,
+        game_root,
+        connect_to_server,
+        cachedir_override,
+        true,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn start_launch(
+    app_handle: tauri::AppHandle,
+    launch_guard: tauri::State<'_, LaunchGuard>,
+    monitor: tauri::State<'_, ProcessMonitor>,
+    appid: String,
+    workshop_path: String,
+    extra_args: Option<Vec<String>>,
+    server: Option<ServerProfile>,
+    steam_start_timeout_secs: Option<u64>,
+    game_root: Option<String>,
+    connect_to_server: Option<bool>,
+    cachedir_override: Option<String>,
+    debug_console: bool,
+) -> Result<String, LauncherError> {
+    // A second Play click inside the window would queue another -applaunch before PZ shows up.
+    // Checked before any phase goes out so an ignored click leaves the launch UI untouched, and
+    // claimed under the same lock so two clicks can't both get through.
+    {
+        let mut pending_since = launch_guard.pending_since.lock().unwrap();
+        if pending_since.is_some_and(|since| since.elapsed() < LAUNCH_DEBOUNCE) {
+            return Ok("already-launching".into());
+        }
+        *pending_since = Some(Instant::now());
+    }
+    emit_launch_phase(
+        &app_handle,
+        LaunchPhase::Validating,
+        serde_json::json!({ "workshop_path": workshop_path }),
+    );
+    let prepared = (|| -> Result<LaunchJob, LauncherError> {
+        if workshop_path.is_empty() {
+            return Err(LauncherError::WorkshopPathEmpty);
+        }
+        log::info!("play: workshop_path={}", workshop_path);
+        let extra_args = validate_extra_args(extra_args.unwrap_or_default())?;
+        let invoked_at = Instant::now();
+        let snapshot = monitor.snapshot();
+        // Another -applaunch does nothing useful while PZ is open (maybe stuck on a crash dialog).
+        if let Some(exe) = pz_process_running(&snapshot) {
+            log::info!("play: {} is already running", exe);
+            return Err(LauncherError::PzAlreadyRunning(exe));
+        }
+        let junction = cachedir_junction(Path::new(&workshop_path));
+        if junction.is_junction && !junction.target_available {
+            return Err(format!(
+                "The junction at {} points to {}, which is missing. Reconnect the drive or repair the junction before launching.",
+                junction.path,
+                junction.target.as_deref().unwrap_or("an unknown location")
+            ).into());
+        }
+        validate_workshop_path(Path::new(&workshop_path))?;
+        let steam_root = require_steam_root()?;
+        log::info!("play: steam_root={}", steam_root);
+        let libs = parse_libraryfolders(&steam_root);
+        if !pz_install_complete(&libs) {
+            log::error!("play: Project Zomboid install is not complete");
+            return Err(LauncherError::PzNotReady);
+        }
+        let game_root = remembered_game_root(&app_handle, game_root);
+        let install_dir = resolve_apply_dest(&libs, game_root.as_deref())?;
+        log::info!("play: install_dir={}", install_dir.display());
+        // Default to the workshop Zomboid folder; Mods may be a junction to another drive
+        let cachedir =
+            match remembered_cachedir(&app_handle, cachedir_override, &steam_root, &libs)? {
+                Some(dir) => dir,
+                None => workshop_zomboid_root(Path::new(&workshop_path)),
+            };
+        log::info!("play: cachedir={}", cachedir.display());
+        // Ensure the cachedir exists
+        fs::create_dir_all(&cachedir)
+            .map_err(|e| format!("Failed to create cachedir {}: {}", cachedir.display(), e))?;
+        let modpack_version = read_modpack_version(Path::new(&workshop_path));
+        if let Some(current) = &modpack_version {
+            if let Some(previous) = cachedir_version_mismatch(&cachedir, current) {
+                log::info!(
+                    "play: saves were made with modpack {}, now {}",
+                    previous,
+                    current
+                );
+                let _ = app_handle.emit(
+                    "cachedir-version-mismatch",
+                    serde_json::json!({ "previous": previous, "current": current }),
+                );
+            }
+        }
+
+        Ok(LaunchJob {
+            appid,
+            workshop_path,
+            steam_root,
+            install_dir,
+            cachedir,
+            modpack_version,
+            extra_args,
+            // Offline/solo mode opens to the main menu instead of auto-joining.
+            server: if connect_to_server.unwrap_or(true) {
+                Some(server.unwrap_or_default())
+            } else {
+                None
+            },
+            steam_start_timeout: steam_start_timeout_secs
+                .map(Duration::from_secs)
+                .unwrap_or(STEAM_START_TIMEOUT),
+            invoked_at,
+            debug_console,
+        })
+    })();
+    let job = match prepared {
+        Ok(job) => job,
+        Err(err) => {
+            *launch_guard.pending_since.lock().unwrap() = None;
+            emit_launch_phase(&app_handle, LaunchPhase::Error, serde_json::json!(&err));
+            report_launch_outcome(&app_handle, Some(&err));
+            return Err(err);
+        }
+    };
+    set_pending_launch(
+        &app_handle,
+        Some(settings::PendingLaunch {
+            workshop_path: job.workshop_path.clone(),
+            started_at: unix_now(),
+        }),
+    );
+    thread::spawn(move || {
+        let result = launch_and_watch(app_handle.clone(), job);
+        report_launch_outcome(&app_handle, result.as_ref().err());
+        if let Err(err) = result {
+            set_pending_launch(&app_handle, None);
+            *app_handle
+                .state::<LaunchGuard>()
+                .pending_since
+                .lock()
+                .unwrap() = None;
+            log::error!("play: {}", err);
+            emit_launch_phase(&app_handle, LaunchPhase::Error, serde_json::json!(&err));
+            let _ = app_handle.emit("pz-launch-failed", &err);
+        }
+    });
+    Ok("launching".into())
+}
+
+fn emit_setup_step(
+    app_handle: &tauri::AppHandle,
+    step: &str,
+    status: &str,
+    detail: &str,
+    action: Option<&str>,
+) {
+    let _ = app_handle.emit(
+        "setup-step",
+        serde_json::json!({
+            "step": step,
+            "status": status,
+            "detail": detail,
+            "action": action,
+        }),
+    );
+}
+
+// Runs detection, subscription, optimizations and a connection test in order, stopping at the
+// first step the player has to fix themselves.
+#[tauri::command]
+fn first_run_setup(app_handle: tauri::AppHandle, workshop_id: String) -> FirstRunResult {
+    let blocked = |step: &str, detail: &str, action: &str, workshop_path: String| {
+        emit_setup_step(&app_handle, step, "blocked", detail, Some(action));
+        FirstRunResult {
+            completed: false,
+            blocked_at: Some(step.to_string()),
+            action: Some(action.to_string()),
+            workshop_path,
+        }
+    };
+
+    emit_setup_step(
+        &app_handle,
+        "detect",
+        "running",
+        "Looking for Project Zomboid",
+        None,
+    );
+    let detected = match auto_detect(vec![workshop_id.clone()]) {
+        Ok(d) => d,
+        Err(err) => {
+            return blocked(
+                "detect",
+                &err.to_string(),
+                "Check the workshop ID configured in the launcher.",
+                String::new(),
+            )
+        }
+    };
+    if !detected.pz_installed {
+        let detail = detected
+            .install_issue
+            .unwrap_or_else(|| "Project Zomboid is not installed".into());
+        return blocked(
+            "detect",
+            &detail,
+            "Install or verify Project Zomboid in Steam, then run setup again.",
+            String::new(),
+        );
+    }
+    emit_setup_step(&app_handle, "detect", "ok", &detected.steam_root, None);
+
+    emit_setup_step(
+        &app_handle,
+        "subscription",
+        "running",
+        "Checking the modpack download",
+        None,
+    );
+    let workshop_path = detected.workshop_path;
+    if workshop_path.is_empty() {
+        let _ = open_workshop(workshop_id);
+        return blocked(
+            "subscription",
+            "The 13th Pandemic workshop item is not downloaded",
+            "Subscribe in Steam, wait for the download to finish, then run setup again.",
+            workshop_path,
+        );
+    }
+    emit_setup_step(&app_handle, "subscription", "ok", &workshop_path, None);
+
+    emit_setup_step(
+        &app_handle,
+        "optimizations",
+        "running",
+        "Applying optimization files",
+        None,
+    );
+    if let Err(err) =
+        apply_optimizations(app_handle.clone(), workshop_path.clone(), None, None, None)
+    {
+        return blocked(
+            "optimizations",
+            &err.to_string(),
+            "Fix the problem above or skip optimizations from the settings panel.",
+            workshop_path,
+        );
+    }
+    emit_setup_step(
+        &app_handle,
+        "optimizations",
+        "ok",
+        "Optimizations in place",
+        None,
+    );
+
+    emit_setup_step(
+        &app_handle,
+        "connection",
+        "running",
+        "Contacting the server",
+        None,
+    );
+    match run_get_server_status(SERVER_IP.to_string()) {
+        Ok(status) if status.ping_ms.is_some() => {
+            emit_setup_step(&app_handle, "connection", "ok", &status.ip, None)
+        }
+        Ok(status) => emit_setup_step(
+            &app_handle,
+            "connection",
+            "warning",
+            &format!("{} resolved but did not answer ping", status.ip),
+            None,
+        ),
+        Err(err) => {
+            return blocked(
+                "connection",
+                &err.to_string(),
+                "Check your internet connection and firewall, then run setup again.",
+                workshop_path,
+            )
+        }
+    }
+
+    FirstRunResult {
+        completed: true,
+        blocked_at: None,
+        action: None,
+        workshop_path,
+    }
+}
+
+// `--detect <workshop_id>` for support scripts. Release builds have no console on Windows, so
+// redirect stdout to a file (`launcher.exe --detect 3487726294 > detect.json`).
+fn detect_cli_report(workshop_id: String) -> serde_json::Value {
+    let detect = match auto_detect(vec![workshop_id]) {
+        Ok(resp) => serde_json::json!(resp),
+        Err(err) => serde_json::json!({ "error": err }),
+    };
+    let game_root = match resolve_game_root() {
+        Ok(root) => serde_json::json!(root),
+        Err(err) => serde_json::json!({ "error": err }),
+    };
+    serde_json::json!({
+      "launcher_version": env!("CARGO_PKG_VERSION"),
+      "detect": detect,
+      "game_root": game_root
+    })
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|a| a == "--detect") {
+        let workshop_id = args.get(pos + 1).cloned().unwrap_or_default();
+        let report = detect_cli_report(workshop_id);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+        std::process::exit(0);
+    }
     // The launcher detects Steam/workshop paths, starts Project Zomboid with the modpack cachedir, and offers optional optimizations.
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .manage(LaunchGuard::default())
+        .manage(ProcessMonitor::new(PROCESS_POLL_INTERVAL))
+        .manage(SessionWatch::default())
+        .setup(|app| {
+            // Logging is best-effort; the launcher still works without a writable log dir.
+            let _ = logging::init(app.handle());
+            watch_steam_status(app.handle().clone());
+            offer_launch_resume(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             auto_detect,
             open_workshop,
@@ -624,8 +5289,410 @@ fn main() {
             check_optimizations,
             open_launcher_log,
             append_launcher_log,
-            write_launcher_log
+            write_launcher_log,
+            pz_ownership_by_account,
+            apply_and_verify,
+            shader_cache_size,
+            clear_shader_cache,
+            offline_readiness,
+            steam_bigpicture_active,
+            detect_workshop_collection,
+            check_outbound_port,
+            apply_history,
+            describe_layout,
+            persist_launch_args_check,
+            pick_game_root,
+            detect_stale_pack,
+            clean_stale_pack,
+            check_install_completeness,
+            average_launch_time,
+            defender_realtime_enabled,
+            restore_pz_config,
+            gpu_compatibility,
+            first_run_setup,
+            find_duplicate_workshop_content,
+            clean_duplicate_workshop,
+            apply_required_files,
+            filesystem_case_sensitivity,
+            export_client_manifest,
+            check_cachedir_junction,
+            repair_cachedir_junction,
+            process_monitor_status,
+            family_share_workshop_warning,
+            revert_optimizations,
+            server_status,
+            list_servers,
+            workshop_download_state,
+            cancel_session_watch,
+            settings::load_settings,
+            settings::save_settings,
+            clean_cachedir,
+            open_logs,
+            logging::open_log_file,
+            kill_pz,
+            system_memory_mb,
+            set_pz_heap,
+            check_workshop_path,
+            modpack_version,
+            check_modpack_update,
+            server_info,
+            preflight_connectivity,
+            open_missing_workshop_items,
+            check_launcher_update,
+            list_pz_installs,
+            verify_modpack,
+            open_steam_url,
+            open_steam_console,
+            subscribe_and_wait,
+            optimization_status,
+            factory_reset,
+            read_changelog,
+            open_changelog,
+            telemetry::set_telemetry_enabled,
+            gpu_info,
+            steam_running,
+            dismiss_pending_launch,
+            backup_usage,
+            prune_backups,
+            play_debug
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri app");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri app")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                app_handle.state::<SessionWatch>().cancel();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pz13p-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn same_size_content_mismatch_is_not_applied() {
+        let root = temp_dir("same-size");
+        let (src, dst) = (root.join("src"), root.join("dst"));
+        fs::create_dir_all(src.join("media")).unwrap();
+        fs::create_dir_all(dst.join("media")).unwrap();
+        fs::write(src.join("media").join("a.lua"), b"good contents").unwrap();
+        fs::write(dst.join("media").join("a.lua"), b"bad! contents").unwrap();
+        let manifest = root.join("optimizations.json");
+
+        let applied =
+            optimizations_applied(&src, &dst, &manifest, None, &mut ApplyStats::default()).unwrap();
+        assert!(!applied);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn kill_and_snapshot_do_not_deadlock() {
+        // A zero interval makes every snapshot() refresh, so it always takes both locks.
+        let monitor = ProcessMonitor::new(Duration::ZERO);
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let killer = monitor.clone();
+        let kill_done = done_tx.clone();
+        thread::spawn(move || {
+            for _ in 0..20 {
+                killer.kill(&["pz13p-no-such-process"]);
+            }
+            let _ = kill_done.send(());
+        });
+        let poller = monitor.clone();
+        thread::spawn(move || {
+            for _ in 0..20 {
+                poller.snapshot();
+            }
+            let _ = done_tx.send(());
+        });
+        for _ in 0..2 {
+            done_rx
+                .recv_timeout(Duration::from_secs(30))
+                .expect("kill and snapshot deadlocked");
+        }
+    }
+
+    #[test]
+    fn restore_rejects_paths_outside_the_install() {
+        let root = temp_dir("restore-unsafe");
+        let (dest, backup) = (root.join("install"), root.join("backup"));
+        fs::create_dir_all(&dest).unwrap();
+        fs::create_dir_all(&backup).unwrap();
+        fs::write(root.join("outside.txt"), b"keep").unwrap();
+        let manifest = BackupManifest {
+            created_at: 0,
+            replaced: Vec::new(),
+            created: vec!["../outside.txt".to_string()],
+        };
+        fs::write(
+            backup.join("manifest.json"),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        assert!(restore_backup(&dest, &backup).is_err());
+        assert_eq!(fs::read(root.join("outside.txt")).unwrap(), b"keep");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn failed_copy_leaves_destination_unchanged() {
+        let root = temp_dir("copy-rollback");
+        let (src, dst) = (root.join("src"), root.join("dst"));
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fs::write(src.join("a.txt"), b"new a").unwrap();
+        fs::write(src.join("b.txt"), b"new b").unwrap();
+        fs::write(src.join("c.txt"), b"new c").unwrap();
+        fs::write(dst.join("b.txt"), b"old b").unwrap();
+        // A directory where a file should go makes that copy fail regardless of privileges.
+        fs::create_dir_all(dst.join("c.txt")).unwrap();
+
+        let err = copy_dir_replace(&src, &dst, None, false, &mut |_, _, _, _| {})
+            .err()
+            .expect("copy into a directory should fail");
+        assert!(err.to_string().contains("c.txt"));
+        assert!(!dst.join("a.txt").exists());
+        assert_eq!(fs::read(dst.join("b.txt")).unwrap(), b"old b");
+        assert!(dst.join("c.txt").is_dir());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn copy_reuses_existing_folder_casing() {
+        let root = temp_dir("copy-casing");
+        let (src, dst) = (root.join("src"), root.join("dst"));
+        fs::create_dir_all(src.join("media").join("lua")).unwrap();
+        fs::create_dir_all(dst.join("Media").join("lua")).unwrap();
+        fs::write(src.join("media").join("lua").join("a.lua"), b"new").unwrap();
+        fs::write(dst.join("Media").join("lua").join("a.lua"), b"old").unwrap();
+
+        let report = copy_dir_replace(&src, &dst, None, false, &mut |_, _, _, _| {}).unwrap();
+        assert_eq!(report.replaced, 1);
+        assert_eq!(
+            fs::read(dst.join("Media").join("lua").join("a.lua")).unwrap(),
+            b"new"
+        );
+        assert_eq!(fs::read_dir(&dst).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn copied_files_keep_source_mtime() {
+        let root = temp_dir("copy-mtime");
+        let (src, dst) = (root.join("src"), root.join("dst"));
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("a.txt"), b"contents").unwrap();
+        let old = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+        filetime::set_file_mtime(src.join("a.txt"), filetime::FileTime::from_system_time(old))
+            .unwrap();
+
+        copy_dir_replace(&src, &dst, None, false, &mut |_, _, _, _| {}).unwrap();
+        let src_mtime = fs::metadata(src.join("a.txt")).unwrap().modified().unwrap();
+        let dst_mtime = fs::metadata(dst.join("a.txt")).unwrap().modified().unwrap();
+        let drift = src_mtime
+            .duration_since(dst_mtime)
+            .or_else(|_| dst_mtime.duration_since(src_mtime))
+            .unwrap();
+        assert!(drift <= Duration::from_secs(1));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn self_referential_symlink_does_not_loop() {
+        let root = temp_dir("symlink-loop");
+        fs::create_dir_all(root.join("Mods")).unwrap();
+        fs::write(root.join("Mods").join("a.txt"), b"a").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("Mods").join("loop")).unwrap();
+
+        assert_eq!(list_files_walk(&root, true).unwrap().len(), 1);
+        assert_eq!(list_files_walk(&root, false).unwrap().len(), 1);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn recorded_manifest_does_not_hide_corrupted_dest() {
+        let root = temp_dir("corrupt-dest");
+        let (src, dst) = (root.join("src"), root.join("dst"));
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fs::write(src.join("a.lua"), b"good contents").unwrap();
+        fs::write(dst.join("a.lua"), b"good contents").unwrap();
+        let manifest = root.join("optimizations.json");
+        assert!(
+            optimizations_applied(&src, &dst, &manifest, None, &mut ApplyStats::default()).unwrap()
+        );
+
+        fs::write(dst.join("a.lua"), b"bad! contents").unwrap();
+        let applied =
+            optimizations_applied(&src, &dst, &manifest, None, &mut ApplyStats::default()).unwrap();
+        assert!(!applied);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn libraryfolders_paths_are_unescaped() {
+        let vdf = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"contentid"		"4251183236583921880"
+		"totalsize"		"0"
+		"update_clean_bytes_tally"		"151302574"
+		"time_last_update_verified"		"1717430321"
+		"apps"
+		{
+			"228980"		"291001589"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\Games\\\"Steam\" Library"
+		"label"		"path"
+		"contentid"		"7215376201859471342"
+		"totalsize"		"1000202039296"
+		"apps"
+		{
+			"108600"		"5213478137"
+		}
+	}
+}
+"#;
+        assert_eq!(
+            library_paths_from_vdf(vdf),
+            vec![
+                PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                PathBuf::from(r#"D:\Games\"Steam" Library"#),
+            ]
+        );
+    }
+
+    #[test]
+    fn vdf_tokenizer_skips_comments_and_handles_braces() {
+        let tokens = vdf_tokens("// header\n\"a\"{\"b\" \"c\\\\d\"}");
+        assert_eq!(
+            tokens,
+            vec![
+                VdfToken::Str("a".into()),
+                VdfToken::Open,
+                VdfToken::Str("b".into()),
+                VdfToken::Str("c\\d".into()),
+                VdfToken::Close,
+            ]
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn native_path_string_uses_backslashes_on_windows() {
+        assert_eq!(
+            native_path_string(Path::new("C:/Program Files (x86)/Steam/steamapps")),
+            "C:\\Program Files (x86)\\Steam\\steamapps"
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn native_path_string_keeps_native_separators() {
+        assert_eq!(
+            native_path_string(Path::new("/home/user/.steam/steam/steamapps")),
+            "/home/user/.steam/steam/steamapps"
+        );
+    }
+
+    // 10.255.255.1 is non-routable, so nothing ever answers; the query must give up on its own.
+    #[test]
+    fn server_query_returns_within_timeout_on_unroutable_address() {
+        let timeout = Duration::from_millis(500);
+        let started = Instant::now();
+        let result = a2s_info_exchange("10.255.255.1", SERVER_PORT, timeout);
+        assert!(result.is_err());
+        assert!(started.elapsed() < timeout + Duration::from_secs(2));
+
+        let addr = "10.255.255.1:16261".parse().unwrap();
+        let started = Instant::now();
+        let _ = TcpStream::connect_timeout(&addr, timeout);
+        assert!(started.elapsed() < timeout + Duration::from_secs(2));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_copy_is_never_seen_partially_written() {
+        let dir = temp_dir("atomic-copy");
+        let len = 4 * 1024 * 1024;
+        let (a, b) = (dir.join("a.bin"), dir.join("b.bin"));
+        fs::write(&a, vec![b'a'; len]).unwrap();
+        fs::write(&b, vec![b'b'; len]).unwrap();
+        let dst = dir.join("dst.bin");
+        fs::copy(&a, &dst).unwrap();
+
+        let done = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let (dst, done) = (dst.clone(), done.clone());
+            thread::spawn(move || {
+                while !done.load(Ordering::SeqCst) {
+                    let data = fs::read(&dst).unwrap();
+                    assert_eq!(data.len(), len);
+                    assert!(data.iter().all(|&c| c == data[0]));
+                }
+            })
+        };
+        for round in 0..20 {
+            let src = if round % 2 == 0 { &b } else { &a };
+            copy_file_checked(src, &dst, false, &mut |_| {}).unwrap();
+        }
+        done.store(true, Ordering::SeqCst);
+        reader.join().unwrap();
+
+        let leftovers: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(TEMP_COPY_MARKER))
+            .collect();
+        assert!(leftovers.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn normalize_workshop_id_accepts_bare_id() {
+        assert_eq!(normalize_workshop_id(" 3487726294 ").unwrap(), "3487726294");
+    }
+
+    #[test]
+    fn normalize_workshop_id_extracts_id_from_url() {
+        assert_eq!(
+            normalize_workshop_id(
+                "https://steamcommunity.com/sharedfiles/filedetails/?id=3487726294&searchtext="
+            )
+            .unwrap(),
+            "3487726294"
+        );
+    }
+
+    #[test]
+    fn normalize_workshop_id_rejects_junk() {
+        for raw in [
+            "",
+            "12 34",
+            "../3487726294",
+            "abc",
+            "https://example.com/?id=x1",
+        ] {
+            assert!(matches!(
+                normalize_workshop_id(raw),
+                Err(LauncherError::InvalidWorkshopId(_))
+            ));
+        }
+    }
 }
@@ -0,0 +1,149 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use sysinfo::System;
+
+#[derive(Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub cmd: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct ProcessSnapshot {
+    pub processes: Vec<ProcessInfo>,
+}
+
+impl ProcessSnapshot {
+    pub fn find(&self, name: &str) -> Option<&ProcessInfo> {
+        self.processes
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_running(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+}
+
+struct Shared {
+    system: Mutex<System>,
+    latest: Mutex<Option<(Instant, ProcessSnapshot)>>,
+    subscribers: Mutex<Vec<Sender<ProcessSnapshot>>>,
+    refreshes: Mutex<u64>,
+}
+
+// One System instance for the whole app: callers share a snapshot that is refreshed at most once
+// per interval, and a single polling thread feeds every subscriber while any are alive.
+#[derive(Clone)]
+pub struct ProcessMonitor {
+    interval: Duration,
+    shared: Arc<Shared>,
+}
+
+impl ProcessMonitor {
+    pub fn new(interval: Duration) -> Self {
+        ProcessMonitor {
+            interval,
+            shared: Arc::new(Shared {
+                system: Mutex::new(System::new()),
+                latest: Mutex::new(None),
+                subscribers: Mutex::new(Vec::new()),
+                refreshes: Mutex::new(0),
+            }),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn refresh_count(&self) -> u64 {
+        *self.shared.refreshes.lock().unwrap()
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.shared.subscribers.lock().unwrap().len()
+    }
+
+    // Returns the cached snapshot unless it is older than the interval.
+    pub fn snapshot(&self) -> ProcessSnapshot {
+        let mut latest = self.shared.latest.lock().unwrap();
+        if let Some((at, snapshot)) = latest.as_ref() {
+            if at.elapsed() < self.interval {
+                return snapshot.clone();
+            }
+        }
+        let snapshot = self.refresh();
+        *latest = Some((Instant::now(), snapshot.clone()));
+        snapshot
+    }
+
+    // Receives a fresh snapshot every interval until the receiver is dropped.
+    pub fn subscribe(&self) -> Receiver<ProcessSnapshot> {
+        let (tx, rx) = mpsc::channel();
+        let mut subscribers = self.shared.subscribers.lock().unwrap();
+        let start_thread = subscribers.is_empty();
+        subscribers.push(tx);
+        drop(subscribers);
+        if start_thread {
+            let monitor = self.clone();
+            thread::spawn(move || monitor.run());
+        }
+        rx
+    }
+
+    // Terminates every process whose name matches one of `names`, returning how many were killed.
+    pub fn kill(&self, names: &[&str]) -> u32 {
+        let killed = {
+            let mut system = self.shared.system.lock().unwrap();
+            system.refresh_processes();
+            system
+                .processes()
+                .values()
+                .filter(|p| names.iter().any(|n| p.name().eq_ignore_ascii_case(n)))
+                .filter(|p| p.kill())
+                .count() as u32
+        };
+        // Force the next snapshot to see the processes gone. `system` is released first because
+        // snapshot() takes `latest` before `system`.
+        *self.shared.latest.lock().unwrap() = None;
+        killed
+    }
+
+    fn run(&self) {
+        loop {
+            let snapshot = self.snapshot();
+            let mut subscribers = self.shared.subscribers.lock().unwrap();
+            subscribers.retain(|tx| tx.send(snapshot.clone()).is_ok());
+            if subscribers.is_empty() {
+                return;
+            }
+            drop(subscribers);
+            thread::sleep(self.interval);
+        }
+    }
+
+    fn refresh(&self) -> ProcessSnapshot {
+        let mut system = self.shared.system.lock().unwrap();
+        system.refresh_processes();
+        *self.shared.refreshes.lock().unwrap() += 1;
+        ProcessSnapshot {
+            processes: system
+                .processes()
+                .values()
+                .map(|p| ProcessInfo {
+                    pid: p.pid().as_u32(),
+                    name: p.name().to_string(),
+                    cmd: p.cmd().to_vec(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, sync::Mutex};
+use tauri::Manager;
+
+use crate::error::LauncherError;
+
+// Written when play starts and cleared once -applaunch fires; still present on startup means
+// the launcher was closed while waiting for Steam.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PendingLaunch {
+    pub workshop_path: String,
+    pub started_at: u64,
+}
+
+// Choices remembered between runs; unknown keys from older versions are ignored on load.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LauncherSettings {
+    pub optimizations_applied_hash: Option<String>,
+    // Chosen when several Steam libraries contain a PZ install
+    pub game_root: Option<String>,
+    // Used by play instead of the workshop Zomboid folder when set
+    pub cachedir_override: Option<String>,
+    // Opt-in only; see telemetry.rs for exactly what is sent
+    pub telemetry_enabled: bool,
+    pub telemetry_endpoint: Option<String>,
+    pub pending_launch: Option<PendingLaunch>,
+}
+
+pub fn settings_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, LauncherError> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join("settings.json"))
+        .map_err(|e| LauncherError::Other(e.to_string()))
+}
+
+// A missing or unreadable file just means first run.
+#[tauri::command]
+pub fn load_settings(app_handle: tauri::AppHandle) -> LauncherSettings {
+    settings_path(&app_handle)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+// Held across every load-modify-save so concurrent writers (launch thread, commands) can't
+// drop each other's changes.
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+fn write_settings(
+    app_handle: &tauri::AppHandle,
+    settings: &LauncherSettings,
+) -> Result<(), LauncherError> {
+    let path = settings_path(app_handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(settings)?;
+    fs::write(path, json).map_err(LauncherError::from)
+}
+
+#[tauri::command]
+pub fn save_settings(
+    app_handle: tauri::AppHandle,
+    settings: LauncherSettings,
+) -> Result<(), LauncherError> {
+    let _guard = SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    write_settings(&app_handle, &settings)
+}
+
+pub fn update_settings<F>(app_handle: &tauri::AppHandle, update: F) -> Result<(), LauncherError>
+where
+    F: FnOnce(&mut LauncherSettings),
+{
+    let _guard = SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut settings = load_settings(app_handle.clone());
+    update(&mut settings);
+    write_settings(app_handle, &settings)
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use std::thread;
+
+use crate::{error::LauncherError, settings};
+
+const TELEMETRY_URL: &str = "https://13thpandemic.mywire.org/launcher/telemetry";
+
+// Deliberately coarse: no paths, user names or Steam IDs ever go in here.
+#[derive(Serialize)]
+pub struct Outcome {
+    pub launcher_version: &'static str,
+    pub os: &'static str,
+    // "launch" or "apply-optimizations"
+    pub operation: &'static str,
+    pub pz_ready: bool,
+    pub steam_found: bool,
+    // Empty for anything other than a launch
+    pub launch_phase_reached: String,
+    pub error_kind: Option<&'static str>,
+}
+
+impl Outcome {
+    pub fn new(
+        operation: &'static str,
+        pz_ready: bool,
+        steam_found: bool,
+        launch_phase_reached: &str,
+        error_kind: Option<&'static str>,
+    ) -> Self {
+        Outcome {
+            launcher_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            operation,
+            pz_ready,
+            steam_found,
+            launch_phase_reached: launch_phase_reached.to_string(),
+            error_kind,
+        }
+    }
+}
+
+// Fire-and-forget: `build` and the POST both run on a background thread, and only when the
+// player opted in, so a slow or dead endpoint can never hold up a launch.
+pub fn report<F>(app_handle: &tauri::AppHandle, build: F)
+where
+    F: FnOnce() -> Outcome + Send + 'static,
+{
+    let settings = settings::load_settings(app_handle.clone());
+    if !settings.telemetry_enabled {
+        return;
+    }
+    let endpoint = settings
+        .telemetry_endpoint
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| TELEMETRY_URL.to_string());
+    thread::spawn(move || {
+        let outcome = build();
+        let client = match reqwest::blocking::Client::builder()
+            .timeout(crate::NET_TIMEOUT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(_) => return,
+        };
+        if let Err(err) = client.post(&endpoint).json(&outcome).send() {
+            log::info!("telemetry: report not sent: {}", err);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn set_telemetry_enabled(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+) -> Result<bool, LauncherError> {
+    settings::update_settings(&app_handle, |settings| settings.telemetry_enabled = enabled)?;
+    Ok(enabled)
+}
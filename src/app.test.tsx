@@ -79,7 +79,7 @@ describe("App", () => {
 
     await waitFor(() =>
       expect(invokeMock).toHaveBeenCalledWith("auto_detect", {
-        workshopId: "3487726294",
+        workshopIds: ["3487726294"],
       })
     );
     await waitFor(() => expect(invokeMock).toHaveBeenCalledWith("resolve_game_root"));
@@ -107,7 +107,7 @@ describe("App", () => {
 
     await waitFor(() =>
       expect(invokeMock).toHaveBeenCalledWith("auto_detect", {
-        workshopId: "3487726294",
+        workshopIds: ["3487726294"],
       })
     );
 
@@ -158,7 +158,7 @@ describe("App", () => {
 
     await waitFor(() =>
       expect(invokeMock).toHaveBeenCalledWith("auto_detect", {
-        workshopId: "3487726294",
+        workshopIds: ["3487726294"],
       })
     );
 
@@ -7,6 +7,8 @@ import "./app.css";
 import bannerImage from "../assets/launcher-banner.png";
 
 const WORKSHOP_ID = "3487726294";
+// Main pack first; any dependency mods follow.
+const WORKSHOP_IDS = [WORKSHOP_ID];
 const APP_ID = "108600";
 const SERVER_HOST = "13thpandemic.mywire.org";
 const CLIENT_VERSION = "1.0.2";
@@ -17,9 +19,23 @@ type TabKey = "home" | "profile" | "config" | "play";
 type LauncherStatus = "detecting" | "ready" | "missing";
 type PlayState = "idle" | "launching" | "playing";
 
+type WorkshopItemStatus = {
+  id: string;
+  path: string | null;
+  found: boolean;
+};
+
+type JunctionStatus = {
+  is_junction: boolean;
+  path: string;
+  target: string | null;
+  target_available: boolean;
+};
+
 type DetectResponse = {
   steam_root: string;
   workshop_path: string;
+  workshop_items?: WorkshopItemStatus[];
 };
 
 type OptimizationResult = {
@@ -70,6 +86,10 @@ const toMessage = (error: unknown): string => {
   if (error instanceof Error) {
     return error.message;
   }
+  // Backend commands reject with { kind, message }
+  if (typeof error === "object" && error !== null && "message" in error) {
+    return String((error as { message: unknown }).message);
+  }
   return String(error);
 };
 
@@ -225,12 +245,31 @@ function App() {
       }
       try {
         const result = await invoke<DetectResponse>("auto_detect", {
-          workshopId: WORKSHOP_ID,
+          workshopIds: WORKSHOP_IDS,
         });
         setWorkshopPath(result.workshop_path ?? "");
+        const missingDependencies = (result.workshop_items ?? [])
+          .slice(1)
+          .filter((item) => !item.found)
+          .map((item) => item.id);
+        if (missingDependencies.length > 0) {
+          appendLog(`Missing dependency mods: ${missingDependencies.join(", ")}.`);
+        }
         if (result.workshop_path) {
           setStatus("ready");
           appendLog(`Workshop found at ${result.workshop_path}.`);
+          try {
+            const junction = await invoke<JunctionStatus | undefined>("check_cachedir_junction", {
+              workshopPath: result.workshop_path,
+            });
+            if (junction?.is_junction && !junction.target_available) {
+              appendLog(
+                `Warning: ${junction.path} points to ${junction.target ?? "a missing location"}, which is disconnected. Reconnect the drive before launching.`
+              );
+            }
+          } catch (error) {
+            appendLog(`Junction check failed: ${toMessage(error)}`);
+          }
           try {
             const applied = await invoke<boolean>("check_optimizations", {
               workshopPath: result.workshop_path,
@@ -421,6 +460,9 @@ function App() {
   useEffect(() => {
     let unlistenLaunch: (() => void) | null = null;
     let unlistenEnd: (() => void) | null = null;
+    let unlistenFailed: (() => void) | null = null;
+    let unlistenMismatch: (() => void) | null = null;
+    let unlistenResume: (() => void) | null = null;
     const setup = async () => {
       unlistenLaunch = await listen("pz-session-launched", (event) => {
         setPlayState("playing");
@@ -431,10 +473,33 @@ function App() {
       });
       unlistenEnd = await listen("pz-session-ended", (event) => {
         setPlayState("idle");
-        const payload = event.payload as { cachedir?: string; found?: boolean } | null;
+        const payload = event.payload as {
+          cachedir?: string;
+          found?: boolean;
+          cancelled?: boolean;
+        } | null;
+        if (payload?.cancelled) {
+          logRef.current("Stopped watching the game session.");
+          return;
+        }
         const found = payload?.found ? "found" : "not found";
         logRef.current(`Session ended (${found}).`);
       });
+      // play returns before Steam is up, so launch failures arrive as an event.
+      unlistenFailed = await listen("pz-launch-failed", (event) => {
+        setPlayState("idle");
+        logRef.current(`Play failed: ${toMessage(event.payload)}`);
+      });
+      unlistenMismatch = await listen("cachedir-version-mismatch", (event) => {
+        const payload = event.payload as { previous?: string; current?: string } | null;
+        logRef.current(
+          `Warning: saves were made with modpack ${payload?.previous ?? "?"}, now ${payload?.current ?? "?"}. Back up your saves before playing.`
+        );
+      });
+      // The previous launcher window closed while waiting for Steam.
+      unlistenResume = await listen("launch-resume-available", () => {
+        logRef.current("The last launch was interrupted before the game started. Press PLAY to resume.");
+      });
     };
     void setup();
     return () => {
@@ -444,6 +509,15 @@ function App() {
       if (unlistenEnd) {
         unlistenEnd();
       }
+      if (unlistenFailed) {
+        unlistenFailed();
+      }
+      if (unlistenMismatch) {
+        unlistenMismatch();
+      }
+      if (unlistenResume) {
+        unlistenResume();
+      }
     };
   }, []);
 